@@ -0,0 +1,500 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Read, Write},
+    sync::Arc,
+};
+
+use sudoru_core::{
+    Board, Constraint, Difficulty, Seed, Shape, Solutions, Strategy, Symmetry, batch,
+    board::from_symbol,
+    constraint,
+    format::{self, Format},
+    fpuzzles,
+    gattai::{Gattai, Layout},
+    solve,
+};
+
+pub const USAGE: &str = "\
+usage:
+    sudoru [--seed CODE | --daily] [--size N] [--diagonal] [--killer] [--jigsaw]
+           [--comparison] [--constraints LIST] [--gattai LAYOUT] [--shuffle]
+           [collection file]
+    sudoru --edit FILE [--size N] [--diagonal] [--constraints LIST]
+    sudoru solve [--diagonal] [--constraints LIST] [puzzle | file]
+    sudoru generate [--count N] [--size 4|6|9|12|16|25] [--diagonal] [--constraints LIST]
+                    [--difficulty easy|medium|hard|expert] [--seed CODE]
+                    [--symmetry none|rotational|rotational90|horizontal|vertical|diagonal]
+                    [--minimal] [--killer [--no-givens]] [--jigsaw] [--comparison]
+                    [--gattai twin|butterfly|samurai] [--threads N]
+    sudoru rate [--diagonal] [--constraints LIST] [file]
+    sudoru convert [--from line|sdk|grid|fpuzzles] [--to line|sdk|grid|fpuzzles] [file]
+    sudoru canonical [--unique] [file]
+    sudoru transform [--shuffle] [--seed CODE] [--rotate 90|180|270]
+                     [--flip horizontal|vertical] [--transpose] [--relabel 123456789] [file]
+
+    --seed CODE         the puzzle of a seed code, or the first of several generated
+    --daily             play the daily puzzle
+    --size N            board size, 9 by default
+    --diagonal          X-Sudoku, both main diagonals hold every number once
+    --killer            Killer Sudoku, cages add up to their sum
+    --no-givens         Killer Sudoku with nothing but the cages
+    --jigsaw            Jigsaw Sudoku, irregular regions instead of segments
+    --comparison        Greater-than Sudoku, touching cells compared
+    --constraints LIST  extra rules separated by spaces, like `anti-knight odd:0,4`
+    --gattai LAYOUT     overlapping 9×9 grids: twin, butterfly or samurai
+    --shuffle           play a collection's puzzles as random equivalents
+    --edit FILE         write a puzzle into FILE, Ctrl+S saves
+    --count N           how many puzzles to generate
+    --difficulty LEVEL  how hard generated puzzles are rated
+    --symmetry KIND     place the givens of plain puzzles symmetrically
+    --minimal           only keep givens needed for a unique solution
+    --threads N         generate on N threads, every core by default
+    --from, --to FMT    formats to convert between
+    --unique            only write puzzles that aren't equivalent to an earlier one
+    --rotate DEGREES    turn clockwise
+    --flip DIRECTION    mirror horizontally or vertically
+    --transpose         mirror across the main diagonal
+    --relabel DIGITS    number n becomes the n-th digit
+
+Files default to stdin when left out or given as `-`. See README.md for more.
+";
+
+/// Run a subcommand without opening a window.
+/// Returns `None` when `args` isn't a subcommand, so the game should start.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, args) = args.split_first()?;
+
+    let result = match command.as_str() {
+        "solve" => solve(args),
+        "generate" => generate(args),
+        "rate" => rate(args),
+        "convert" => convert(args),
+        "canonical" => canonical(args),
+        "transform" => transform(args),
+        "help" | "-h" | "--help" => {
+            print!("{USAGE}");
+            Ok(())
+        }
+        _ => return None,
+    };
+
+    Some(match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{command}: {e}");
+            1
+        }
+    })
+}
+
+/// `--name value` pairs, `--flag`s and positional arguments
+struct Args<'a> {
+    options: Vec<(&'a str, &'a str)>,
+    flags: Vec<&'a str>,
+    positional: Vec<&'a str>,
+}
+
+impl<'a> Args<'a> {
+    fn parse(args: &'a [String], names: &[&str], flags: &[&str]) -> Result<Self, String> {
+        let mut options = Vec::new();
+        let mut set_flags = Vec::new();
+        let mut positional = Vec::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if names.contains(&name) => {
+                    let value = args.next().ok_or(format!("--{name} needs a value"))?;
+                    options.push((name, value.as_str()));
+                }
+                Some(name) if flags.contains(&name) => set_flags.push(name),
+                Some(name) => return Err(format!("unknown option --{name}\n\n{USAGE}")),
+                None => positional.push(arg.as_str()),
+            }
+        }
+        Ok(Self {
+            options,
+            flags: set_flags,
+            positional,
+        })
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+
+    /// whether `--name` was given at all, as a flag or with a value
+    fn given(&self, name: &str) -> bool {
+        self.flag(name) || self.options.iter().any(|(n, _)| *n == name)
+    }
+
+    fn get<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, String>
+    where
+        T::Err: std::fmt::Display,
+    {
+        Ok(self.get_opt(name)?.unwrap_or(default))
+    }
+
+    fn get_opt<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, String>
+    where
+        T::Err: std::fmt::Display,
+    {
+        match self.options.iter().rev().find(|(n, _)| *n == name) {
+            Some((_, value)) => value
+                .parse()
+                .map(Some)
+                .map_err(|e| format!("--{name}: {e}")),
+            None => Ok(None),
+        }
+    }
+
+    /// contents of the file in the first positional argument, or stdin
+    fn input(&self) -> Result<String, String> {
+        match self.positional.first() {
+            Some(&path) if path != "-" => {
+                fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))
+            }
+            _ => {
+                let mut text = String::new();
+                io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|e| e.to_string())?;
+                Ok(text)
+            }
+        }
+    }
+}
+
+fn output(text: &str) -> Result<(), String> {
+    let mut stdout = io::stdout().lock();
+    match stdout
+        .write_all(text.as_bytes())
+        .and_then(|_| stdout.flush())
+    {
+        Ok(()) => Ok(()),
+        // the reader went away, like `head` does, which isn't an error
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => std::process::exit(0),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// the puzzles to work on and how many input lines were rejected
+fn puzzles(args: &Args, format: Format) -> Result<(Vec<Board>, usize), String> {
+    // a single puzzle can be passed directly instead of a file
+    let (mut boards, rejected) = match args.positional.first().and_then(|arg| arg.parse().ok()) {
+        Some(board) => (vec![board], 0),
+        None => read(format, &args.input()?)?,
+    };
+    let constraints: String = args.get("constraints", String::new())?;
+    for board in &mut boards {
+        board.diagonals |= args.flag("diagonal");
+        board
+            .constraints
+            .extend(parse_constraints(&constraints, board.size())?);
+    }
+    Ok((boards, rejected))
+}
+
+/// Every puzzle in `text` and how many lines didn't hold one.
+/// Those are reported and skipped, so the rest still gets done.
+fn read(format: Format, text: &str) -> Result<(Vec<Board>, usize), String> {
+    if !matches!(format, Format::Line) {
+        let boards = format.read(text).map_err(|e| e.to_string())?;
+        return Ok((boards, 0));
+    }
+    let (puzzles, errors) = format::parse(text);
+    for (line, e) in &errors {
+        eprintln!("line {line}: {e}");
+    }
+    let boards = puzzles.into_iter().map(|puzzle| puzzle.board).collect();
+    Ok((boards, errors.len()))
+}
+
+/// fails a command that had to skip `rejected` input lines, after it did the rest
+fn check_rejected(rejected: usize) -> Result<(), String> {
+    match rejected {
+        0 => Ok(()),
+        n => Err(format!("{n} line(s) without a valid puzzle")),
+    }
+}
+
+fn solve(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["constraints"], &["diagonal"])?;
+    let mut failed = 0;
+
+    let (boards, rejected) = puzzles(&args, Format::Line)?;
+    for board in boards {
+        match solve::solutions(&board) {
+            Solutions::Unique(solution) => output(&Format::Line.write(&solution))?,
+            Solutions::Multiple => {
+                eprintln!("{}: multiple solutions", board.line());
+                if let Some(solution) = solve::solve(&board) {
+                    output(&Format::Line.write(&solution))?;
+                }
+            }
+            Solutions::None => {
+                eprintln!("{}: no solution", board.line());
+                failed += 1;
+            }
+        }
+    }
+
+    check_rejected(rejected)?;
+    match failed {
+        0 => Ok(()),
+        n => Err(format!("{n} puzzle(s) without a solution")),
+    }
+}
+
+fn generate(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(
+        args,
+        &[
+            "count",
+            "size",
+            "difficulty",
+            "seed",
+            "symmetry",
+            "threads",
+            "constraints",
+            "gattai",
+        ],
+        &[
+            "minimal",
+            "diagonal",
+            "killer",
+            "no-givens",
+            "jigsaw",
+            "comparison",
+        ],
+    )?;
+    let count: usize = args.get("count", 1)?;
+    let first: Seed = args.get("seed", Seed::random())?;
+    if let Some(layout) = args.get_opt::<Layout>("gattai")? {
+        // the layout picks the grids and their rules
+        let ignored = [
+            "size",
+            "difficulty",
+            "symmetry",
+            "threads",
+            "constraints",
+            "minimal",
+            "diagonal",
+            "killer",
+            "no-givens",
+            "jigsaw",
+            "comparison",
+        ];
+        if let Some(name) = ignored.iter().find(|&&name| args.given(name)) {
+            return Err(format!("--gattai doesn't work with --{name}"));
+        }
+        for n in 0..count {
+            let seed = Seed(first.0.wrapping_add(n as u64));
+            let gattai = Gattai::generate(layout, seed);
+            output(&format!(
+                "{} {seed} {layout} {} clues\n",
+                gattai.line(),
+                gattai.clue_count()
+            ))?;
+        }
+        return Ok(());
+    }
+    let shape: Shape = args.get("size", Shape::STANDARD)?;
+    let empty = Board {
+        diagonals: args.flag("diagonal"),
+        constraints: parse_constraints(&args.get("constraints", String::new())?, shape.size())?,
+        ..Board::with_shape(shape)
+    };
+    let threads: usize = args.get("threads", batch::default_threads())?;
+    let difficulty: Difficulty = args.get("difficulty", Difficulty::Medium)?;
+    let symmetry = args.get("symmetry", Symmetry::None)?;
+    // only plain puzzles have their givens placed symmetrically
+    let plain = ["killer", "jigsaw", "comparison", "minimal"];
+    if let Some(flag) = plain.iter().find(|&&flag| args.flag(flag))
+        && symmetry != Symmetry::None
+    {
+        return Err(format!("--symmetry doesn't work with --{flag}"));
+    }
+    let strategy = match symmetry {
+        _ if args.flag("killer") => Strategy::Killer {
+            difficulty,
+            givens: !args.flag("no-givens"),
+        },
+        _ if args.flag("jigsaw") => Strategy::Jigsaw(difficulty),
+        _ if args.flag("comparison") => Strategy::Comparison(difficulty),
+        _ if args.flag("minimal") => Strategy::Minimal(difficulty),
+        Symmetry::None => Strategy::Unique(difficulty),
+        symmetry => Strategy::Symmetric(difficulty, symmetry),
+    };
+
+    let mut result = Ok(());
+    let made = batch::generate_batch(&empty, strategy, count, first, threads, |seed, board| {
+        let rating = solve::rate(&board).expect("generated puzzles are unique");
+        result = output(&format!(
+            "{}{} {seed} {rating} {} clues\n",
+            board.line(),
+            format::rules_comment(&board),
+            board.clue_count(),
+        ));
+        result.is_ok()
+    });
+    let Some(made) = made else {
+        return Err("no puzzle follows these rules".to_owned());
+    };
+    result?;
+    if made < count {
+        return Err(format!(
+            "only found {made} distinct puzzle(s) for these rules"
+        ));
+    }
+    Ok(())
+}
+
+/// writes every puzzle with its difficulty and clue count, which is itself a collection file
+fn rate(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["constraints"], &["diagonal"])?;
+
+    let (boards, rejected) = puzzles(&args, Format::Line)?;
+    for board in boards {
+        let rating = match solve::rate(&board) {
+            Some(difficulty) => difficulty.name(),
+            None => "invalid",
+        };
+        let minimal = if board.is_minimal() { " minimal" } else { "" };
+        output(&format!(
+            "{}{} {rating} {} clues{minimal}\n",
+            board.line(),
+            format::rules_comment(&board),
+            board.clue_count(),
+        ))?;
+    }
+    check_rejected(rejected)
+}
+
+fn convert(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &["from", "to"], &[])?;
+    let from: Format = args.get("from", Format::Line)?;
+    let to: Format = args.get("to", Format::Line)?;
+
+    let (boards, rejected) = read(from, &args.input()?)?;
+    for (i, board) in boards.iter().enumerate() {
+        let left_out = fpuzzles::left_out(board);
+        if to == Format::FPuzzles && !left_out.is_empty() {
+            return Err(format!(
+                "{}: f-puzzles has no {}",
+                board.line(),
+                left_out.join(" ")
+            ));
+        }
+        if i != 0 {
+            output(to.separator())?;
+        }
+        output(&to.write(board))?;
+    }
+    check_rejected(rejected)
+}
+
+fn canonical(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args, &[], &["unique"])?;
+    let mut seen = HashSet::new();
+
+    let (boards, rejected) = puzzles(&args, Format::Line)?;
+    for board in boards {
+        let canonical = board.canonical();
+        if !args.flag("unique") {
+            output(&format!("{canonical}\n"))?;
+        } else if seen.insert(canonical) {
+            output(&Format::Line.write(&board))?;
+        }
+    }
+    check_rejected(rejected)
+}
+
+fn transform(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(
+        args,
+        &["seed", "rotate", "flip", "relabel"],
+        &["shuffle", "transpose"],
+    )?;
+
+    let mut rng = args.get("seed", Seed::random())?.rng();
+    let quarter_turns = match args.get("rotate", 0)? {
+        0 => 0,
+        90 => 1,
+        180 => 2,
+        270 => 3,
+        n => return Err(format!("--rotate: can't rotate by {n} degrees")),
+    };
+    let flip: Option<String> = args.get_opt("flip")?;
+    let relabel = match args.get_opt::<String>("relabel")? {
+        Some(labels) => Some(parse_labels(&labels)?),
+        None => None,
+    };
+
+    let (boards, rejected) = puzzles(&args, Format::Line)?;
+    for mut board in boards {
+        if args.flag("shuffle") {
+            board = board.shuffled(&mut rng);
+        }
+        for _ in 0..quarter_turns {
+            board = board
+                .rotated()
+                .map_err(|e| format!("--rotate: {}: {e}", board.line()))?;
+        }
+        board = match flip.as_deref() {
+            None => board,
+            Some("horizontal") => board
+                .flipped_horizontally()
+                .map_err(|e| format!("--flip: {}: {e}", board.line()))?,
+            Some("vertical") => board
+                .flipped_vertically()
+                .map_err(|e| format!("--flip: {}: {e}", board.line()))?,
+            Some(other) => return Err(format!("--flip: unknown direction {other:?}")),
+        };
+        if args.flag("transpose") {
+            board = board.transposed();
+        }
+        if let Some(labels) = &relabel {
+            if labels.len() != board.width() {
+                return Err(format!(
+                    "--relabel: {} numbers for a {} board",
+                    labels.len(),
+                    board.shape
+                ));
+            }
+            board = board
+                .relabelled(labels)
+                .map_err(|e| format!("--relabel: {}: {e}", board.line()))?;
+        }
+        output(&Format::Line.write(&board))?;
+    }
+    check_rejected(rejected)
+}
+
+/// constraints separated by spaces the way puzzle lines list them, for a board of `size` cells
+pub fn parse_constraints(text: &str, size: usize) -> Result<Vec<Arc<dyn Constraint>>, String> {
+    text.split_whitespace()
+        .map(|word| {
+            constraint::parse(word, size).ok_or_else(|| format!("invalid constraint {word:?}"))
+        })
+        .collect()
+}
+
+/// a permutation of the numbers 1 to n, written like a puzzle line
+fn parse_labels(text: &str) -> Result<Vec<u8>, String> {
+    let invalid = || format!("--relabel: {text:?} isn't a permutation like 123456789");
+
+    let labels: Vec<u8> = text
+        .chars()
+        .map(from_symbol)
+        .collect::<Option<_>>()
+        .ok_or_else(invalid)?;
+
+    let mut sorted = labels.clone();
+    sorted.sort();
+    if !sorted.iter().copied().eq(1..=labels.len() as u8) {
+        return Err(invalid());
+    }
+    Ok(labels)
+}
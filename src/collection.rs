@@ -0,0 +1,275 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use sudoru_core::{
+    Seed,
+    board::{Board, Pencil},
+    format::{Puzzle, parse},
+    fpuzzles,
+};
+
+use crate::{cli, storage};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Status {
+    #[default]
+    Unsolved,
+    InProgress,
+    Solved,
+}
+
+/// Rules from `--diagonal` and `--constraints`, added to every puzzle of a collection
+#[derive(Clone, Debug, Default)]
+pub struct Rules {
+    pub diagonals: bool,
+    /// constraint words, parsed for the size of each puzzle
+    pub constraints: String,
+}
+
+impl Rules {
+    fn add_to(&self, board: &mut Board) -> Result<(), String> {
+        board.diagonals |= self.diagonals;
+        let constraints = cli::parse_constraints(&self.constraints, board.size())?;
+        board.constraints.extend(constraints);
+        Ok(())
+    }
+}
+
+/// A file of puzzles, one per line or as f-puzzles data, together with the player's progress
+pub struct Collection {
+    pub path: PathBuf,
+    pub puzzles: Vec<Puzzle>,
+    pub status: Vec<Status>,
+    /// boards of puzzles that were started but not finished
+    pub progress: Vec<Option<Board>>,
+    pub index: usize,
+    /// puzzles are shuffled into equivalent ones, puzzle `n` with seed `shuffle + n`
+    pub shuffle: Option<Seed>,
+}
+
+impl Collection {
+    /// Once a collection has been opened with `shuffle`,
+    /// it stays shuffled the same way so saved progress still fits.
+    pub fn open(path: impl AsRef<Path>, shuffle: bool, rules: &Rules) -> io::Result<Self> {
+        let path = path.as_ref();
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+        let text = fs::read_to_string(&path)?;
+        let mut puzzles = if fpuzzles::is_fpuzzles(&text) {
+            fpuzzles::read(&text)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?
+        } else {
+            let (puzzles, errors) = parse(&text);
+            for (line, e) in errors {
+                eprintln!("{}: line {line}: {e}", path.display());
+            }
+            puzzles
+        };
+        for puzzle in &mut puzzles {
+            rules
+                .add_to(&mut puzzle.board)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+
+        if puzzles.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no puzzles found in file",
+            ));
+        }
+
+        let count = puzzles.len();
+        let mut collection = Self {
+            path,
+            puzzles,
+            status: vec![Status::Unsolved; count],
+            progress: vec![None; count],
+            index: 0,
+            shuffle: None,
+        };
+
+        let saved = storage::read(collection.progress_file());
+        collection.shuffle = saved
+            .as_deref()
+            .and_then(saved_shuffle)
+            .or_else(|| shuffle.then(Seed::random));
+
+        if let Some(Seed(seed)) = collection.shuffle {
+            for (i, puzzle) in collection.puzzles.iter_mut().enumerate() {
+                let mut rng = Seed(seed.wrapping_add(i as u64)).rng();
+                puzzle.board = puzzle.board.shuffled(&mut rng);
+            }
+        }
+        if let Some(text) = saved {
+            collection.load_progress(&text);
+        }
+
+        Ok(collection)
+    }
+
+    pub fn name(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    pub fn current(&self) -> &Puzzle {
+        &self.puzzles[self.index]
+    }
+
+    /// the board to play for the current puzzle, including any progress
+    pub fn board(&self) -> Board {
+        self.progress[self.index]
+            .clone()
+            .unwrap_or_else(|| self.current().board.clone())
+    }
+
+    /// move to another puzzle, wrapping around both ends
+    pub fn go_to(&mut self, index: usize) -> Board {
+        self.index = index % self.len();
+        self.save_progress();
+        self.board()
+    }
+    pub fn next(&mut self) -> Board {
+        self.go_to(self.index + 1)
+    }
+    pub fn prev(&mut self) -> Board {
+        self.go_to(self.index + self.len() - 1)
+    }
+
+    /// remember the state of the current puzzle
+    pub fn record(&mut self, board: &Board) {
+        let status = if board.is_solved() {
+            Status::Solved
+        } else if *board != self.current().board {
+            Status::InProgress
+        } else {
+            Status::Unsolved
+        };
+
+        let i = self.index;
+        // finishing a puzzle is permanent, replaying it doesn't undo that
+        if self.status[i] != Status::Solved {
+            self.status[i] = status;
+        }
+        self.progress[i] = (status == Status::InProgress).then(|| board.clone());
+
+        self.save_progress();
+    }
+
+    pub fn solved_count(&self) -> usize {
+        self.status.iter().filter(|s| **s == Status::Solved).count()
+    }
+
+    /// file in the data directory that holds progress for this collection
+    fn progress_file(&self) -> PathBuf {
+        let hash = storage::hash(self.path.to_string_lossy().as_bytes());
+        Path::new("collections").join(format!("{}-{hash:016x}.txt", self.name()))
+    }
+
+    /*
+    shuffle 0000000000003
+    index 12
+    3 solved
+    5 progress 1.3....... colors 12:13 40:5 corner 2:14
+    */
+    fn load_progress(&mut self, text: &str) {
+        for line in text.lines() {
+            let mut words = line.split_whitespace();
+            let (Some(first), Some(second)) = (words.next(), words.next()) else {
+                continue;
+            };
+
+            if first == "index" {
+                self.index = second.parse().unwrap_or(0) % self.len();
+                continue;
+            }
+            let Some(i) = first.parse::<usize>().ok().filter(|i| *i < self.len()) else {
+                continue;
+            };
+
+            match second {
+                "solved" => self.status[i] = Status::Solved,
+                "progress" => {
+                    let Some(board) = read_progress(&self.puzzles[i].board, words) else {
+                        continue;
+                    };
+                    if self.status[i] != Status::Solved {
+                        self.status[i] = Status::InProgress;
+                    }
+                    self.progress[i] = Some(board);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn save_progress(&self) {
+        let mut text = String::new();
+        if let Some(seed) = self.shuffle {
+            text += &format!("shuffle {seed}\n");
+        }
+        text += &format!("index {}\n", self.index);
+
+        for (i, status) in self.status.iter().enumerate() {
+            if *status == Status::Solved {
+                text += &format!("{i} solved\n");
+            }
+            if let Some(board) = &self.progress[i] {
+                text += &format!("{i} progress {}\n", progress_text(board));
+            }
+        }
+
+        if let Err(e) = storage::write(self.progress_file(), &text) {
+            eprintln!("failed to save progress: {e}");
+        }
+    }
+}
+
+/// a board's progress as it's saved: its cells, then its colors and pencil marks
+/// if it has any
+pub fn progress_text(board: &Board) -> String {
+    let mut text = board.line();
+    if let Some(colors) = board.colors_line() {
+        text += &format!(" colors {colors}");
+    }
+    for pencil in [Pencil::Corner, Pencil::Center] {
+        if let Some(marks) = board.pencil_line(pencil) {
+            text += &format!(" {} {marks}", pencil.name());
+        }
+    }
+    text
+}
+
+/// the progress written by `progress_text` on top of `puzzle`, `None` if it isn't a board
+pub fn read_progress<'a>(
+    puzzle: &Board,
+    mut words: impl Iterator<Item = &'a str>,
+) -> Option<Board> {
+    let mut board = puzzle.restore_line(words.next()?)?;
+    // the words after `colors`, `corner` or `center`
+    let mut section = "";
+    for word in words {
+        match word {
+            "colors" | "corner" | "center" => section = word,
+            _ if section == "colors" => board.read_colors([word]),
+            _ if section == "corner" => board.read_pencil(Pencil::Corner, [word]),
+            _ if section == "center" => board.read_pencil(Pencil::Center, [word]),
+            _ => (),
+        }
+    }
+    Some(board)
+}
+
+/// the shuffle seed in a progress file
+fn saved_shuffle(text: &str) -> Option<Seed> {
+    text.lines()
+        .find_map(|line| line.strip_prefix("shuffle "))
+        .and_then(|code| code.trim().parse().ok())
+}
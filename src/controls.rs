@@ -0,0 +1,75 @@
+use macroquad::prelude::*;
+
+use crate::ui::UI;
+
+/// a button of the controls over the number pad, each with a key that does the same
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    Previous,
+    Next,
+    Daily,
+    Random,
+    Theme,
+    Pause,
+}
+
+impl Control {
+    const ALL: [Control; 6] = [
+        Control::Previous,
+        Control::Next,
+        Control::Daily,
+        Control::Random,
+        Control::Theme,
+        Control::Pause,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Control::Previous => "Prev",
+            Control::Next => "Next",
+            Control::Daily => "Daily",
+            Control::Random => "Random",
+            Control::Theme => "Theme",
+            Control::Pause => "Pause",
+        }
+    }
+}
+
+impl UI {
+    /// the controls in a row, where they are on screen
+    fn control_buttons(&self) -> Vec<(Rect, Control)> {
+        if !self.panel {
+            return Vec::new();
+        }
+        let area = self.layout.controls;
+        let w = area.w / Control::ALL.len() as f32;
+        let gap = area.h * 0.06;
+        Control::ALL
+            .iter()
+            .enumerate()
+            .map(|(k, &control)| {
+                let x = area.x + w * k as f32;
+                let rect = Rect::new(x + gap, area.y + gap, w - gap * 2., area.h - gap * 2.);
+                (rect, control)
+            })
+            .collect()
+    }
+
+    /// the control clicked this frame
+    pub fn control_clicked(&self) -> Option<Control> {
+        if !is_mouse_button_pressed(MouseButton::Left) {
+            return None;
+        }
+        let mouse: Vec2 = mouse_position().into();
+        self.control_buttons()
+            .into_iter()
+            .find(|(rect, _)| rect.contains(mouse))
+            .map(|(_, control)| control)
+    }
+
+    pub fn draw_controls(&self) {
+        for (rect, control) in self.control_buttons() {
+            self.draw_button(rect, control.label(), None);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use chrono::Datelike;
+use sudoru_core::{Board, Daily, Date};
+
+use crate::{
+    collection::{self, Status},
+    storage,
+};
+
+const FILE: &str = "daily.txt";
+
+/// the local calendar date
+pub fn today() -> Date {
+    let now = chrono::Local::now().date_naive();
+    Date::new(now.year(), now.month(), now.day())
+}
+
+/// The daily puzzles played so far, and the one currently selected
+pub struct Dailies {
+    pub today: Date,
+    pub daily: Daily,
+    /// the selected daily's puzzle, without progress
+    pub puzzle: Board,
+    pub status: BTreeMap<Date, Status>,
+    /// boards of dailies that were started but not finished
+    pub progress: BTreeMap<Date, Board>,
+}
+
+impl Dailies {
+    /// today's daily, with the history of earlier ones
+    pub fn load(today: Date) -> Self {
+        let daily = Daily::new(today);
+        let mut dailies = Self {
+            today,
+            daily,
+            puzzle: daily.board(),
+            status: BTreeMap::new(),
+            progress: BTreeMap::new(),
+        };
+        dailies.load_history();
+        dailies
+    }
+
+    pub fn board(&self) -> Board {
+        self.progress
+            .get(&self.daily.date)
+            .cloned()
+            .unwrap_or_else(|| self.puzzle.clone())
+    }
+
+    pub fn status(&self, date: Date) -> Status {
+        self.status.get(&date).copied().unwrap_or_default()
+    }
+
+    /// select the daily of another day, never one after today
+    pub fn go_to(&mut self, date: Date) -> Board {
+        let date = date.min(self.today);
+        if date != self.daily.date {
+            self.daily = Daily::new(date);
+            self.puzzle = self.daily.board();
+        }
+        self.board()
+    }
+    pub fn next(&mut self) -> Board {
+        self.go_to(self.daily.date.add_days(1))
+    }
+    pub fn prev(&mut self) -> Board {
+        self.go_to(self.daily.date.add_days(-1))
+    }
+
+    /// the day before the first unsolved one, counting back from today.
+    /// An unsolved today doesn't break the streak yet.
+    pub fn streak(&self) -> u32 {
+        let mut date = self.today;
+        if self.status(date) != Status::Solved {
+            date = date.add_days(-1);
+        }
+
+        let mut streak = 0;
+        while self.status(date) == Status::Solved {
+            streak += 1;
+            date = date.add_days(-1);
+        }
+        streak
+    }
+
+    /// remember the state of the selected daily
+    pub fn record(&mut self, board: &Board) {
+        let date = self.daily.date;
+        let status = if board.is_solved() {
+            Status::Solved
+        } else if *board != self.puzzle {
+            Status::InProgress
+        } else {
+            Status::Unsolved
+        };
+
+        // finishing a daily is permanent, replaying it doesn't undo that
+        if self.status(date) != Status::Solved {
+            self.status.insert(date, status);
+        }
+        if status == Status::InProgress {
+            self.progress.insert(date, board.clone());
+        } else {
+            self.progress.remove(&date);
+        }
+
+        self.save_history();
+    }
+
+    /*
+    2026-10-18 solved
+    2026-10-19 progress 1.3....... colors 12:13
+    */
+    fn load_history(&mut self) {
+        let Some(text) = storage::read(FILE) else {
+            return;
+        };
+
+        for line in text.lines() {
+            let mut words = line.split_whitespace();
+            let (Some(Ok(date)), Some(kind)) = (words.next().map(str::parse), words.next()) else {
+                continue;
+            };
+
+            match kind {
+                "solved" => {
+                    self.status.insert(date, Status::Solved);
+                }
+                "progress" => {
+                    let Some(board) = collection::read_progress(&Daily::new(date).board(), words)
+                    else {
+                        continue;
+                    };
+                    self.status.entry(date).or_insert(Status::InProgress);
+                    self.progress.insert(date, board);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn save_history(&self) {
+        let mut text = String::new();
+
+        for (date, status) in &self.status {
+            if *status == Status::Solved {
+                text += &format!("{date} solved\n");
+            }
+            if let Some(board) = self.progress.get(date) {
+                text += &format!("{date} progress {}\n", collection::progress_text(board));
+            }
+        }
+
+        if let Err(e) = storage::write(FILE, &text) {
+            eprintln!("failed to save dailies: {e}");
+        }
+    }
+}
@@ -1,41 +1,77 @@
-use crate::board::{CELL_STR, Position};
-use crate::ui::UI;
-use crate::{board::Board, theme::Theme};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, TAU};
+
+use crate::pad::{Button, Entry};
+use crate::theme::Theme;
+use crate::ui::{UI, command_key_down};
 use macroquad::prelude::*;
+use sudoru_core::{
+    board::{Board, CELL_STR, COLORS, Pencil},
+    constraint::{EdgeKind, Mark},
+};
 
 impl UI {
     pub fn draw_borders(&self, _board: &Board) {
-        let Rect { w, h, x, y } = self.board_layout;
+        let Vec2 { x: w, y: h } = self.board_texture.texture.size();
         draw_rectangle_lines(
             0., //
             0.,
             w,
             h,
-            self.theme().border_thick,
+            self.theme().border_thick * self.dpi,
             self.theme().border_color,
         );
     }
 
     pub fn draw(&mut self, board: &mut Board) {
-        clear_background(self.theme().bg);
-        let dpi = screen_dpi_scale();
-
+        self.fit(board);
         self.handle_input(board);
+        self.show(board);
+    }
+
+    /// like `draw`, without taking any input
+    pub fn show(&mut self, board: &Board) {
+        clear_background(self.theme().bg);
+        self.fit(board);
 
         if self.redraw {
             set_camera(&self.board_texture_cam);
             clear_background(self.theme().bg);
+            self.draw_layers(board);
+            set_default_camera();
+        }
 
-            self.draw_cells(board); // draw cells first to avoid overlap
-            self.draw_borders(board);
-            self.draw_squares(board);
+        self.draw_board_texture();
+        self.draw_clues(board);
+        self.draw_header();
+        self.draw_controls();
+        self.draw_pad();
+    }
 
-            set_default_camera();
+    /// cells and clicks go by the side of `board`
+    fn fit(&mut self, board: &Board) {
+        if board.width() != self.side {
+            self.side = board.width();
+            self.redraw = true;
         }
+    }
+
+    /// everything on the board, from the bottom up, onto the board texture
+    pub fn draw_layers(&self, board: &Board) {
+        self.draw_diagonals(board);
+        self.draw_marks(board);
+        self.draw_cells(board); // draw cells first to avoid overlap
+        self.draw_cages(board);
+        self.draw_borders(board);
+        self.draw_squares(board);
+        self.draw_edges(board);
+    }
 
-        let Vec2 { x, y } = self.board_layout.point();
-        let size = self.board_layout.size();
+    /// the part of the board texture in `view` over the board's place on screen
+    pub fn draw_board_texture(&self) {
+        let Vec2 { x, y } = self.layout.board.point();
+        let size = self.layout.board.size();
         let texture = &self.board_texture.texture;
+        let shown = texture.size() / self.zoom;
 
         draw_texture_ex(
             texture,
@@ -44,14 +80,349 @@ impl UI {
             WHITE,
             DrawTextureParams {
                 dest_size: Some(size),
+                source: Some(Rect::new(
+                    self.view.x * texture.width(),
+                    self.view.y * texture.height(),
+                    shown.x,
+                    shown.y,
+                )),
                 ..Default::default()
             },
         );
     }
 
+    /// sums of sandwich constraints, right of their row or under their column
+    pub fn draw_clues(&self, board: &Board) {
+        let Rect { x, y, w, h } = self.layout.board;
+        let side = board.width() as f32;
+        let cell_size = w * self.zoom / side;
+        let font_size = (cell_size * 0.4).min(self.layout.padding * 0.8) as u16;
+        let color = self.theme().cell_fg;
+
+        for constraint in &board.constraints {
+            for mark in constraint.marks(board) {
+                // the middle of the row or column, which can be zoomed out of sight
+                let (text, center) = match mark {
+                    Mark::RowClue(row, sum) => (
+                        sum.to_string(),
+                        vec2(
+                            x + w + self.layout.padding / 2.,
+                            self.on_screen(vec2(0., (row as f32 + 0.5) / side)).y,
+                        ),
+                    ),
+                    Mark::ColClue(col, sum) => (
+                        sum.to_string(),
+                        vec2(
+                            self.on_screen(vec2((col as f32 + 0.5) / side, 0.)).x,
+                            y + h + self.layout.padding / 2.,
+                        ),
+                    ),
+                    _ => continue,
+                };
+                if !(x..x + w).contains(&center.x) && !(y..y + h).contains(&center.y) {
+                    continue;
+                }
+                let size = measure_text(&text, None, font_size, 1.);
+                draw_text_ex(
+                    &text,
+                    center.x - size.width / 2.,
+                    center.y - size.height / 2. + size.offset_y,
+                    TextParams {
+                        font_size,
+                        color,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+    }
+
+    /// draw the header text above the board, shrunk to fit the window
+    pub fn draw_header(&self) {
+        if self.header.is_empty() {
+            return;
+        }
+        let Rect { x, y, w, h } = self.layout.header;
+        let mut font_size = (h * 0.8) as u16;
+        let mut size = measure_text(&self.header, None, font_size, 1.);
+        while size.width > w && font_size > 1 {
+            font_size -= 1;
+            size = measure_text(&self.header, None, font_size, 1.);
+        }
+
+        draw_text_ex(
+            &self.header,
+            x,
+            y + (h - size.height) / 2. + size.offset_y,
+            TextParams {
+                font_size,
+                color: self.theme().cell_fg,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// the main diagonals of X-Sudoku, under the numbers
+    pub fn draw_diagonals(&self, board: &Board) {
+        if !board.diagonals {
+            return;
+        }
+        let size = self.board_texture.texture.width();
+        let &Theme {
+            diagonal_color,
+            diagonal_thick,
+            ..
+        } = self.theme();
+
+        let thick = diagonal_thick * self.dpi;
+        draw_line(0., 0., size, size, thick, diagonal_color);
+        draw_line(size, 0., 0., size, thick, diagonal_color);
+    }
+
+    /// dashed outlines just inside every Killer cage, with the sum in its top-left cell
+    pub fn draw_cages(&self, board: &Board) {
+        if board.cages.is_empty() {
+            return;
+        }
+        let &Theme {
+            bg,
+            cage_color,
+            cage_thick,
+            ..
+        } = self.theme();
+        let cell_size = self.board_texture.texture.width() / board.width() as f32;
+        let inset = cell_size * 0.08;
+        let side = board.width() as isize;
+
+        for cage in &board.cages {
+            let caged = |x: isize, y: isize| {
+                (0..side).contains(&x)
+                    && (0..side).contains(&y)
+                    && cage.cells.contains(&board.index((x as usize, y as usize)))
+            };
+
+            for &index in &cage.cells {
+                let (x, y) = board.coords(index);
+                let (x, y) = (x as isize, y as isize);
+                let (left, top) = (x as f32 * cell_size, y as f32 * cell_size);
+                let (right, bottom) = (left + cell_size, top + cell_size);
+
+                // lines run on to the edge of the cell when the cage goes on that way,
+                // so they join up with the next cell's
+                let (x1, x2) = (
+                    if caged(x - 1, y) { left } else { left + inset },
+                    if caged(x + 1, y) {
+                        right
+                    } else {
+                        right - inset
+                    },
+                );
+                let (y1, y2) = (
+                    if caged(x, y - 1) { top } else { top + inset },
+                    if caged(x, y + 1) {
+                        bottom
+                    } else {
+                        bottom - inset
+                    },
+                );
+
+                let dashed = |from: Vec2, to: Vec2| {
+                    draw_dashed_line(
+                        from,
+                        to,
+                        cell_size * 0.08,
+                        cage_thick * self.dpi,
+                        cage_color,
+                    )
+                };
+                if !caged(x, y - 1) {
+                    dashed(vec2(x1, top + inset), vec2(x2, top + inset));
+                }
+                if !caged(x, y + 1) {
+                    dashed(vec2(x1, bottom - inset), vec2(x2, bottom - inset));
+                }
+                if !caged(x - 1, y) {
+                    dashed(vec2(left + inset, y1), vec2(left + inset, y2));
+                }
+                if !caged(x + 1, y) {
+                    dashed(vec2(right - inset, y1), vec2(right - inset, y2));
+                }
+            }
+
+            let (x, y) = board.coords(cage.corner());
+            let (x, y) = (x as f32 * cell_size, y as f32 * cell_size);
+            let sum = cage.sum.to_string();
+            let font_size = (cell_size * 0.25) as u16;
+            let size = measure_text(&sum, None, font_size, 1.);
+
+            // cover the dashes behind the sum
+            draw_rectangle(
+                x + inset / 2.,
+                y + inset / 2.,
+                size.width + inset,
+                size.height + inset,
+                bg,
+            );
+            draw_text_ex(
+                &sum,
+                x + inset,
+                y + inset + size.offset_y,
+                TextParams {
+                    font_size,
+                    color: cage_color,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    /// what the board's constraints want to show, under the numbers
+    pub fn draw_marks(&self, board: &Board) {
+        let cell_size = self.board_texture.texture.width() / board.width() as f32;
+        let &Theme {
+            mark_color: color,
+            line_color,
+            ..
+        } = self.theme();
+        let cell = |index: usize| {
+            let (x, y) = board.coords(index);
+            Rect::new(
+                x as f32 * cell_size,
+                y as f32 * cell_size,
+                cell_size,
+                cell_size,
+            )
+        };
+
+        for constraint in &board.constraints {
+            for mark in constraint.marks(board) {
+                match mark {
+                    Mark::Shade(index) => {
+                        let Rect { x, y, w, h } = cell(index);
+                        draw_rectangle(x, y, w, h, color);
+                    }
+                    Mark::Circle(index) => {
+                        let center = cell(index).center();
+                        draw_circle(center.x, center.y, cell_size * 0.4, color);
+                    }
+                    Mark::Square(index) => {
+                        let Rect { x, y, w, h } = cell(index);
+                        let inset = cell_size * 0.12;
+                        draw_rectangle(x + inset, y + inset, w - inset * 2., h - inset * 2., color);
+                    }
+                    Mark::Thermometer(cells) => {
+                        let bulb = cell(cells[0]).center();
+                        draw_circle(bulb.x, bulb.y, cell_size * 0.35, line_color);
+                        for pair in cells.windows(2) {
+                            let (from, to) = (cell(pair[0]).center(), cell(pair[1]).center());
+                            draw_line(from.x, from.y, to.x, to.y, cell_size * 0.3, line_color);
+                            draw_circle(to.x, to.y, cell_size * 0.15, line_color);
+                        }
+                    }
+                    Mark::Arrow(cells) => {
+                        let circle = cell(cells[0]).center();
+                        let radius = cell_size * 0.4;
+                        let thick = cell_size * 0.05;
+                        draw_circle_lines(circle.x, circle.y, radius, thick, line_color);
+
+                        // the shaft starts at the edge of the circle
+                        let centers: Vec<Vec2> = cells.iter().map(|&i| cell(i).center()).collect();
+                        let start = circle + (centers[1] - circle).normalize() * radius;
+                        let mut points = vec![start];
+                        points.extend(&centers[1..]);
+                        for pair in points.windows(2) {
+                            draw_line(
+                                pair[0].x, pair[0].y, pair[1].x, pair[1].y, thick, line_color,
+                            );
+                        }
+
+                        let (tip, before) = (points[points.len() - 1], points[points.len() - 2]);
+                        let back = (before - tip).normalize() * cell_size * 0.25;
+                        for side in [
+                            back.rotate(Vec2::from_angle(0.5)),
+                            back.rotate(Vec2::from_angle(-0.5)),
+                        ] {
+                            let end = tip + side;
+                            draw_line(tip.x, tip.y, end.x, end.y, thick, line_color);
+                        }
+                    }
+                    Mark::Color(index, [r, g, b]) => {
+                        // see-through, so the lines of other marks still show
+                        let Rect { x, y, w, h } = cell(index);
+                        draw_rectangle(x, y, w, h, Color::from_rgba(r, g, b, 128));
+                    }
+                    // outside the board, see `draw_clues`
+                    Mark::RowClue(..) | Mark::ColClue(..) => (),
+                    // over the borders, see `draw_edges`
+                    Mark::Edge(..) => (),
+                }
+            }
+        }
+    }
+
+    /// Kropki dots, XV letters and comparisons on the edge between their cells, over the borders
+    pub fn draw_edges(&self, board: &Board) {
+        let cell_size = self.board_texture.texture.width() / board.width() as f32;
+        let &Theme { bg, cell_fg, .. } = self.theme();
+        let center = |index: usize| {
+            let (x, y) = board.coords(index);
+            vec2(x as f32 + 0.5, y as f32 + 0.5) * cell_size
+        };
+
+        for constraint in &board.constraints {
+            for mark in constraint.marks(board) {
+                let Mark::Edge(kind, a, b) = mark else {
+                    continue;
+                };
+                let Vec2 { x, y } = (center(a) + center(b)) / 2.;
+                let radius = cell_size * 0.12;
+
+                match kind {
+                    EdgeKind::White => {
+                        draw_circle(x, y, radius, bg);
+                        draw_circle_lines(x, y, radius, cell_size * 0.03, cell_fg);
+                    }
+                    EdgeKind::Black => draw_circle(x, y, radius, cell_fg),
+                    EdgeKind::Less => {
+                        // the point of the chevron is towards the smaller number
+                        let towards = (center(a) - center(b)).normalize() * cell_size * 0.08;
+                        let across = towards.perp() * 1.5;
+                        let tip = vec2(x, y) + towards;
+                        let thick = cell_size * 0.03;
+                        for end in [tip - towards * 2. + across, tip - towards * 2. - across] {
+                            draw_line(tip.x, tip.y, end.x, end.y, thick, cell_fg);
+                        }
+                    }
+                    EdgeKind::X | EdgeKind::V => {
+                        let text = if kind == EdgeKind::X { "X" } else { "V" };
+                        let font_size = (cell_size * 0.35) as u16;
+                        let size = measure_text(text, None, font_size, 1.);
+                        draw_rectangle(
+                            x - size.width / 2.,
+                            y - size.height / 2.,
+                            size.width,
+                            size.height,
+                            bg,
+                        );
+                        draw_text_ex(
+                            text,
+                            x - size.width / 2.,
+                            y - size.height / 2. + size.offset_y,
+                            TextParams {
+                                font_size,
+                                color: cell_fg,
+                                ..Default::default()
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     pub fn draw_cell_num(&self, cell: Rect, num: u8, highlight: bool) {
         let font = None;
-        let font_size = self.font_size;
+        let font_size = (cell.w * 0.45) as u16;
 
         let num_str = CELL_STR[num as usize - 1];
 
@@ -80,7 +451,7 @@ impl UI {
     pub fn draw_cells(&self, board: &Board) {
         let width = self.board_texture.texture.width();
 
-        let cell_size = width / 9.;
+        let cell_size = width / board.width() as f32;
         let start_x = 0.; //self.board_layout.x;
         let start_y = 0.; //self.board_layout.y;
 
@@ -88,18 +459,21 @@ impl UI {
 
         // Draw the selected cell
         if let Some(pos) = self.selected_cell {
-            let (x, y) = (pos % board.height(), pos / board.width());
+            let (x, y) = board.coords(pos);
             let (x, y) = (x as f32 * cell.w + start_x, y as f32 * cell.h + start_y);
 
             draw_rectangle(x, y, cell.w, cell.h, self.theme().selected_bg);
         }
+        self.draw_colors(board);
 
         for y in 0..board.height() {
             for x in 0..board.width() {
-                let index = (x, y).index();
+                let index = board.index((x, y));
                 if let Some(Some(num)) = board.get(index) {
                     let highlight = Some(index) == self.highlighted_cell;
                     self.draw_cell_num(cell, *num, highlight);
+                } else {
+                    self.draw_pencil(board, index, cell);
                 }
                 cell.x += cell.w;
             }
@@ -108,19 +482,200 @@ impl UI {
         }
     }
 
+    /// Pencil marks of an empty cell, corner marks around its edge from the
+    /// corners in and center marks in a line through the middle
+    fn draw_pencil(&self, board: &Board, index: usize, cell: Rect) {
+        const SPOTS: [(f32, f32); 9] = [
+            (0.2, 0.2),
+            (0.8, 0.2),
+            (0.2, 0.8),
+            (0.8, 0.8),
+            (0.5, 0.2),
+            (0.5, 0.8),
+            (0.2, 0.5),
+            (0.8, 0.5),
+            (0.5, 0.5),
+        ];
+        let color = self.theme().cell_fg;
+        let draw = |text: &str, center: Vec2, font_size: u16| {
+            let size = measure_text(text, None, font_size, 1.);
+            draw_text_ex(
+                text,
+                center.x - size.width / 2.,
+                center.y - size.height / 2. + size.offset_y,
+                TextParams {
+                    font_size,
+                    color,
+                    ..Default::default()
+                },
+            );
+        };
+
+        let font_size = (cell.w * 0.22) as u16;
+        let corner = board.pencil_marks(Pencil::Corner, index);
+        for (&num, (x, y)) in corner.iter().zip(SPOTS) {
+            let spot = cell.point() + vec2(x, y) * cell.size();
+            draw(CELL_STR[num as usize - 1], spot, font_size);
+        }
+
+        let center: String = board
+            .pencil_marks(Pencil::Center, index)
+            .iter()
+            .map(|&num| CELL_STR[num as usize - 1])
+            .collect();
+        if !center.is_empty() {
+            // smaller when there are many
+            let width = measure_text(&center, None, font_size, 1.).width;
+            let scale = (cell.w * 0.8 / width).min(1.);
+            draw(&center, cell.center(), (font_size as f32 * scale) as u16);
+        }
+    }
+
+    /// The player's colors as the background of their cells, a wedge for each
+    /// when a cell has several, starting from the top-left corner
+    fn draw_colors(&self, board: &Board) {
+        let cell_size = self.board_texture.texture.width() / board.width() as f32;
+        // where a line from the center at `angle` leaves the cell
+        let edge = |angle: f32| {
+            let dir = Vec2::from_angle(angle);
+            dir * cell_size / 2. / dir.x.abs().max(dir.y.abs())
+        };
+
+        for index in 0..board.size() {
+            let colors = board.cell_colors(index);
+            let (x, y) = board.coords(index);
+            let center = (vec2(x as f32, y as f32) + 0.5) * cell_size;
+            let wedge = TAU / colors.len() as f32;
+            for (k, &color) in colors.iter().enumerate() {
+                let color = self.theme().palette[color as usize - 1];
+                let (start, end) = (
+                    -3. * FRAC_PI_4 + k as f32 * wedge,
+                    -3. * FRAC_PI_4 + (k + 1) as f32 * wedge,
+                );
+                // the corners in between, so the wedge fills the square
+                let mut angles = vec![start];
+                let mut corner = -3. * FRAC_PI_4 + FRAC_PI_2;
+                while corner < end - 1e-4 {
+                    if corner > start + 1e-4 {
+                        angles.push(corner);
+                    }
+                    corner += FRAC_PI_2;
+                }
+                angles.push(end);
+                for pair in angles.windows(2) {
+                    let (a, b) = (center + edge(pair[0]), center + edge(pair[1]));
+                    draw_triangle(center, a, b, color);
+                }
+            }
+        }
+    }
+
+    /// Typed numbers and those clicked on the number pad go in the selected cell
+    /// the way the entry mode says: as its number, as pencil marks or as colors.
+    /// With Ctrl (Cmd on macOS) held, 1 to 9 give it that color or take it away
+    /// again whatever the mode, 0 clears its colors and Z undoes the last change.
+    /// Delete or Backspace erase the cell.
     pub fn handle_input(&mut self, board: &mut Board) {
-        if let Some((index, num)) = self.insert_num() {
+        let clicked = self.pad_clicked();
+        let command = command_key_down();
+        match clicked {
+            Some(Button::Entry(entry)) => {
+                self.entry = entry;
+                return;
+            }
+            Some(Button::Undo) => return self.undo(board),
+            _ if command && is_key_pressed(KeyCode::Z) => return self.undo(board),
+            _ => (),
+        }
+
+        let before = board.clone();
+        if clicked == Some(Button::Hint) {
+            self.hint(board);
+        } else if let Some(index) = self.selected_cell {
+            let erase = clicked == Some(Button::Erase)
+                || is_key_pressed(KeyCode::Delete)
+                || is_key_pressed(KeyCode::Backspace);
+            let typed = match clicked {
+                Some(Button::Num(num)) => Some((self.entry, num)),
+                _ if command => self.num_key_clicked().map(|num| (Entry::Color, num)),
+                _ => self.num_key_clicked().map(|num| (self.entry, num)),
+            };
+
+            if erase {
+                self.erase(board, index);
+            } else if command && is_key_pressed(KeyCode::Key0) {
+                board.colors[index] = 0;
+            } else if let Some((entry, num)) = typed {
+                self.enter(board, index, entry, num);
+            }
+        }
+
+        if *board != before {
+            self.history.push(before);
             self.redraw = true;
-            if let Err(e) = board.place(index, Some(num)) {
-                println!("{index} => {e:?}");
-                self.highlight(&board, index, Some(num), e);
-            } else {
-                self.highlighted_cell = None;
+        }
+    }
+
+    fn enter(&mut self, board: &mut Board, index: usize, entry: Entry, num: u8) {
+        match entry {
+            Entry::Digit => {
+                self.redraw = true;
+                if let Err(e) = board.place(index, Some(num)) {
+                    self.highlight(board, index, Some(num), e);
+                } else {
+                    self.highlighted_cell = None;
+                }
+            }
+            // pencil marks only show in empty cells
+            Entry::Corner if board.cells[index].is_none() => {
+                board.toggle_pencil(Pencil::Corner, index, num);
             }
+            Entry::Center if board.cells[index].is_none() => {
+                board.toggle_pencil(Pencil::Center, index, num);
+            }
+            Entry::Color if num <= COLORS => board.toggle_color(index, num),
+            _ => (),
+        }
+    }
+
+    /// the player's number in the cell, or its pencil marks and colors when it has none
+    fn erase(&mut self, board: &mut Board, index: usize) {
+        if board.cells[index].is_some() && !board.givens[index] {
+            board.cells[index] = None;
+        } else {
+            board.clear_pencil(index);
+            board.colors[index] = 0;
+        }
+        self.highlighted_cell = None;
+    }
+
+    /// The selected cell's number from the solution, or else the first cell
+    /// that's empty or wrong. That cell gets selected.
+    fn hint(&mut self, board: &mut Board) {
+        let Some(solution) = board.solution() else {
+            return;
+        };
+        let wrong = |i: usize| board.cells[i] != solution.cells[i];
+        let index = (self.selected_cell.filter(|&i| wrong(i)))
+            .or_else(|| (0..board.size()).find(|&i| wrong(i)));
+        if let Some(index) = index {
+            board.cells[index] = solution.cells[index];
+            self.selected_cell = Some(index);
+            self.highlighted_cell = None;
         }
     }
 
-    pub fn draw_squares(&self, _board: &Board) {
+    fn undo(&mut self, board: &mut Board) {
+        if let Some(before) = self.history.pop() {
+            *board = before;
+            self.highlighted_cell = None;
+            self.redraw = true;
+        }
+    }
+
+    /// thin lines around every cell and thick ones between segments,
+    /// which follow the regions of Jigsaw Sudoku
+    pub fn draw_squares(&self, board: &Board) {
         let width = self.board_texture.texture.width();
 
         let &Theme {
@@ -130,33 +685,54 @@ impl UI {
             cell_border: cell_color,
             ..
         } = self.theme();
+        let (square_thick, cell_thick) = (square_thick * self.dpi, cell_thick * self.dpi);
 
-        let size = width / 3.;
-        let cell_size = size / 3.;
-
-        for row in 0..3 {
-            for col in 0..3 {
-                // draw section
-                let x = size * col as f32;
-                let y = size * row as f32;
-                draw_rectangle_lines(x, y, size, size, square_thick, square);
-
-                // draw cells
-                for cell_row in 0..3 {
-                    for cell_col in 0..3 {
-                        let cell = Rect::new(
-                            x + cell_size * cell_col as f32,
-                            y + cell_size * cell_row as f32,
-                            cell_size,
-                            cell_size,
-                        );
+        let side = board.width();
+        let cell_size = width / side as f32;
+        // centred on the edge, so as thick as two segments' outlines used to be
+        let thick = square_thick * 2.;
 
-                        draw_rectangle_lines(
-                            cell.x, cell.y, cell.w, cell.h, cell_thick, cell_color,
-                        );
-                    }
-                }
+        for index in 0..board.size() {
+            let (x, y) = board.coords(index);
+            let (left, top) = (x as f32 * cell_size, y as f32 * cell_size);
+            let (right, bottom) = (left + cell_size, top + cell_size);
+            draw_rectangle_lines(left, top, cell_size, cell_size, cell_thick, cell_color);
+
+            let seg = board.seg_index(index);
+            if x + 1 < side && board.seg_index((x + 1, y)) != seg {
+                draw_line(
+                    right,
+                    top - square_thick,
+                    right,
+                    bottom + square_thick,
+                    thick,
+                    square,
+                );
+            }
+            if y + 1 < side && board.seg_index((x, y + 1)) != seg {
+                draw_line(
+                    left - square_thick,
+                    bottom,
+                    right + square_thick,
+                    bottom,
+                    thick,
+                    square,
+                );
             }
         }
     }
 }
+
+/// a line of dashes `dash` long with gaps as long between them
+fn draw_dashed_line(from: Vec2, to: Vec2, dash: f32, thickness: f32, color: Color) {
+    let length = from.distance(to);
+    let dir = (to - from) / length.max(f32::EPSILON);
+
+    let mut start = 0.;
+    while start < length {
+        let end = (start + dash).min(length);
+        let (a, b) = (from + dir * start, from + dir * end);
+        draw_line(a.x, a.y, b.x, b.y, thickness, color);
+        start += dash * 2.;
+    }
+}
@@ -0,0 +1,410 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver},
+    thread::{self, sleep},
+    time::Duration,
+};
+
+use macroquad::prelude::*;
+use sudoru_core::{
+    Board, Solutions,
+    board::{Cage, ParseError},
+    constraint::{self, Mark},
+    format::{self, Format},
+    fpuzzles, solve,
+};
+
+use crate::ui::{self, UI};
+
+/// keys that add a constraint along the marked cells, and the constraint's word
+const ELEMENTS: [(KeyCode, &str); 9] = [
+    (KeyCode::H, "thermo"),
+    (KeyCode::A, "arrow"),
+    (KeyCode::O, "odd"),
+    (KeyCode::E, "even"),
+    (KeyCode::W, "white"),
+    (KeyCode::B, "black"),
+    (KeyCode::X, "x"),
+    (KeyCode::V, "v"),
+    (KeyCode::L, "less"),
+];
+
+const DIGITS: [KeyCode; 10] = [
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// A puzzle being written, with what the solver makes of it
+struct Editor {
+    board: Board,
+    path: PathBuf,
+    /// saved as f-puzzles data rather than a puzzle line
+    fpuzzles: bool,
+    /// cells shift-clicked in order, for the next cage or constraint
+    marked: Vec<usize>,
+    /// the sum typed so far after K, for a cage around `marked`
+    cage_sum: Option<String>,
+    /// `None` while the solver is still looking
+    solutions: Option<Solutions>,
+    counting: Option<Receiver<Solutions>>,
+    /// what happened last, shown in the header
+    message: String,
+}
+
+/// Write a puzzle into the file at `path`, starting from its first puzzle when
+/// it has one and from `empty` otherwise. Numbers typed are givens, and Ctrl+S
+/// saves the puzzle over that first one, keeping the rest of the file.
+/// F-puzzles files hold one puzzle, those with more aren't opened.
+pub async fn edit(path: PathBuf, empty: Board) {
+    let text = fs::read_to_string(&path).ok();
+    let fpuzzles = match &text {
+        Some(text) => fpuzzles::is_fpuzzles(text),
+        None => path.extension().is_some_and(|ext| ext == "json"),
+    };
+    let mut several = false;
+    let first = text.and_then(|text| {
+        let puzzles = if fpuzzles {
+            let puzzles = fpuzzles::read(&text).unwrap_or_else(|e| {
+                eprintln!("{}: {e}", path.display());
+                Vec::new()
+            });
+            several = puzzles.len() > 1;
+            puzzles
+        } else {
+            let (puzzles, errors) = format::parse(&text);
+            for (line, e) in errors {
+                eprintln!("{}: line {line}: {e}", path.display());
+            }
+            puzzles
+        };
+        puzzles.into_iter().next()
+    });
+    if several {
+        eprintln!(
+            "{}: holds several f-puzzles puzzles, only one can be edited",
+            path.display()
+        );
+        return;
+    }
+
+    let mut editor = Editor {
+        board: first.map_or(empty, |puzzle| puzzle.board),
+        path,
+        fpuzzles,
+        marked: Vec::new(),
+        cage_sum: None,
+        solutions: None,
+        counting: None,
+        message: String::new(),
+    };
+    editor.count();
+    let mut ui = UI::new();
+
+    let min_frame_time = 1. / 30.;
+
+    loop {
+        let frame_time = get_frame_time();
+
+        ui.update();
+        editor.handle_input(&mut ui);
+        editor.poll();
+        ui.header = editor.header();
+
+        ui.draw_editor(&editor.board, &editor.marked);
+
+        // sleep for CPU's sake
+        if frame_time < min_frame_time {
+            let sleep_time = (min_frame_time - frame_time) * 1000.;
+            sleep(Duration::from_millis(sleep_time as u64));
+        }
+        next_frame().await;
+    }
+}
+
+impl Editor {
+    /// Shift+click marks cells, Escape forgets them. Delete clears the selected
+    /// cell, or takes away the cages and constraints on the marked cells.
+    fn handle_input(&mut self, ui: &mut UI) {
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if shift && let Some(cell) = ui.get_cell_clicked() {
+            match self.marked.iter().position(|&i| i == cell) {
+                Some(k) => _ = self.marked.remove(k),
+                None => self.marked.push(cell),
+            }
+            ui.redraw = true;
+        }
+
+        if ui::command_key_down() && is_key_pressed(KeyCode::S) {
+            self.save();
+            return;
+        }
+
+        if let Some(sum) = &mut self.cage_sum {
+            if let Some(digit) = DIGITS.iter().position(|&key| is_key_pressed(key)) {
+                sum.push_str(&digit.to_string());
+            } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+                self.add_cage();
+                ui.redraw = true;
+            } else if is_key_pressed(KeyCode::Escape) {
+                self.cage_sum = None;
+            }
+            return;
+        }
+
+        if let Some((index, num)) = ui.insert_num() {
+            self.set_given(ui, index, Some(num));
+            return;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            self.marked.clear();
+            ui.redraw = true;
+        }
+        if is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace) {
+            if !self.marked.is_empty() {
+                self.remove_elements();
+            } else if let Some(index) = ui.selected_cell {
+                self.set_given(ui, index, None);
+            }
+            ui.redraw = true;
+        }
+
+        if self.marked.is_empty() {
+            return;
+        }
+        if is_key_pressed(KeyCode::K) {
+            self.cage_sum = Some(String::new());
+        }
+        for (key, word) in ELEMENTS {
+            if is_key_pressed(key) {
+                self.add_constraint(word);
+                ui.redraw = true;
+            }
+        }
+    }
+
+    /// put `cell` in `index` as a given, checked against the rules like a player's number
+    fn set_given(&mut self, ui: &mut UI, index: usize, cell: Option<u8>) {
+        ui.redraw = true;
+        let before = self.board.cells[index];
+        self.board.givens[index] = false;
+        self.board.cells[index] = None;
+        match cell.map(|_| self.board.place(index, cell)) {
+            Some(Err(e)) => {
+                self.board.cells[index] = before;
+                ui.highlight(&self.board, index, cell, e);
+            }
+            _ => ui.highlighted_cell = None,
+        }
+        self.board.givens[index] = self.board.cells[index].is_some();
+        if self.board.cells[index] != before {
+            self.changed("");
+        }
+    }
+
+    /// a constraint written `word:marked,cells`, if the givens still fit it
+    fn add_constraint(&mut self, word: &str) {
+        let cells: Vec<String> = self.marked.iter().map(usize::to_string).collect();
+        let word = format!("{word}:{}", cells.join(","));
+        let Some(constraint) = constraint::parse(&word, self.board.size()) else {
+            self.message = format!("{word} doesn't fit these cells");
+            return;
+        };
+        let mut board = self.board.clone();
+        board.constraints.push(constraint);
+        self.replace(board, &word);
+    }
+
+    fn add_cage(&mut self) {
+        let sum = self.cage_sum.take().and_then(|sum| sum.parse().ok());
+        let Some(sum) = sum else {
+            self.message = "a cage needs a sum".to_owned();
+            return;
+        };
+        let cage = Cage::new(self.marked.clone(), sum);
+        if cage.cells.iter().any(|&i| self.board.cage(i).is_some()) {
+            self.message = "cages can't overlap".to_owned();
+            return;
+        }
+        let mut board = self.board.clone();
+        let word = format!("cage {cage}");
+        board.cages.push(cage);
+        self.replace(board, &word);
+    }
+
+    /// take away every cage and constraint on a marked cell
+    fn remove_elements(&mut self) {
+        let on_marked = |cells: &[usize]| cells.iter().any(|i| self.marked.contains(i));
+        let board = &self.board;
+        let unmoved: Vec<usize> = (0..board.size()).collect();
+        let constraints = board.constraints.iter().filter(|c| {
+            // rules of the whole board, like windoku, don't belong to any cells
+            let on_cells = c.moved(&unmoved).is_some();
+            let cells: Vec<usize> = c.marks(board).into_iter().flat_map(mark_cells).collect();
+            !(on_cells && on_marked(&cells))
+        });
+        let mut edited = Board {
+            constraints: constraints.cloned().collect(),
+            ..board.clone()
+        };
+        edited.cages.retain(|cage| !on_marked(&cage.cells));
+        self.marked.clear();
+        if edited != self.board {
+            self.board = edited;
+            self.changed("removed");
+        }
+    }
+
+    /// `board` with new rules instead of the current one, unless they break a given
+    fn replace(&mut self, board: Board, what: &str) {
+        let rules = board.clone();
+        let checked = Board::from_line(&board.line(), |empty| {
+            *empty = Board {
+                cages: rules.cages.clone(),
+                ..rules.empty()
+            };
+            Ok(())
+        });
+        match checked {
+            Ok(board) => {
+                self.board = board;
+                self.marked.clear();
+                self.changed(&format!("added {what}"));
+            }
+            Err(ParseError::Conflict(index, _)) => {
+                self.message = format!("{what} doesn't fit the given in cell {index}");
+            }
+            Err(e) => self.message = e.to_string(),
+        }
+    }
+
+    /// start counting solutions again after the board changed
+    fn changed(&mut self, message: &str) {
+        message.clone_into(&mut self.message);
+        self.count();
+    }
+
+    /// Count solutions of the board on another thread, so the editor doesn't stall.
+    /// Whatever an earlier count finds is ignored.
+    fn count(&mut self) {
+        let (sender, receiver) = mpsc::channel();
+        let board = self.board.clone();
+        thread::spawn(move || {
+            // nobody listens anymore once the board changed again
+            let _ = sender.send(solve::solutions(&board));
+        });
+        self.solutions = None;
+        self.counting = Some(receiver);
+    }
+
+    fn poll(&mut self) {
+        if let Some(counting) = &self.counting
+            && let Ok(solutions) = counting.try_recv()
+        {
+            self.solutions = Some(solutions);
+            self.counting = None;
+        }
+    }
+
+    fn save(&mut self) {
+        let left_out = fpuzzles::left_out(&self.board);
+        let text = if self.fpuzzles {
+            if !left_out.is_empty() {
+                self.message = format!("can't save, f-puzzles has no {}", left_out.join(" "));
+                return;
+            }
+            Format::FPuzzles.write(&self.board)
+        } else {
+            let old = fs::read_to_string(&self.path).unwrap_or_default();
+            format::replace_first(&old, &Format::Line.write(&self.board))
+        };
+        self.message = match fs::write(&self.path, text) {
+            Ok(()) => "saved".to_owned(),
+            Err(e) => format!("failed to save: {e}"),
+        };
+    }
+
+    fn header(&self) -> String {
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let solutions = match &self.solutions {
+            None => "counting solutions...",
+            Some(Solutions::None) => "no solution",
+            Some(Solutions::Unique(_)) => "unique",
+            Some(Solutions::Multiple) => "multiple solutions",
+        };
+        let mut header = format!(
+            "editing {name} - {solutions} - {} givens",
+            self.board.clue_count()
+        );
+        if let Some(sum) = &self.cage_sum {
+            header += &format!(" - cage sum: {sum}_");
+        } else if !self.message.is_empty() {
+            header += &format!(" - {}", self.message);
+        }
+        header
+    }
+}
+
+/// the cells a mark is drawn in, none for clues outside the board
+fn mark_cells(mark: Mark) -> Vec<usize> {
+    match mark {
+        Mark::Shade(i) | Mark::Circle(i) | Mark::Square(i) | Mark::Color(i, _) => vec![i],
+        Mark::Thermometer(cells) | Mark::Arrow(cells) => cells,
+        Mark::Edge(_, a, b) => vec![a, b],
+        Mark::RowClue(..) | Mark::ColClue(..) => Vec::new(),
+    }
+}
+
+impl UI {
+    /// Like `draw`, with the marked cells shaded and joined up in the order they were
+    /// marked. Input is left to the editor.
+    fn draw_editor(&mut self, board: &Board, marked: &[usize]) {
+        clear_background(self.theme().bg);
+
+        if board.width() != self.side {
+            self.side = board.width();
+            self.redraw = true;
+        }
+
+        if self.redraw {
+            set_camera(&self.board_texture_cam);
+            clear_background(self.theme().bg);
+
+            let cell_size = self.board_texture.texture.width() / board.width() as f32;
+            let center = |index: usize| {
+                let (x, y) = board.coords(index);
+                vec2(x as f32 + 0.5, y as f32 + 0.5) * cell_size
+            };
+            for &index in marked {
+                let corner = center(index) - cell_size / 2.;
+                draw_rectangle(
+                    corner.x,
+                    corner.y,
+                    cell_size,
+                    cell_size,
+                    self.theme().selected_bg,
+                );
+            }
+            self.draw_layers(board);
+            for pair in marked.windows(2) {
+                let (from, to) = (center(pair[0]), center(pair[1]));
+                let color = self.theme().line_color;
+                draw_line(from.x, from.y, to.x, to.y, cell_size * 0.05, color);
+            }
+
+            set_default_camera();
+        }
+
+        self.draw_board_texture();
+        self.draw_clues(board);
+        self.draw_header();
+    }
+}
@@ -0,0 +1,201 @@
+use std::{thread::sleep, time::Duration};
+
+use macroquad::prelude::*;
+use sudoru_core::{
+    Seed,
+    gattai::{Gattai, Layout},
+};
+
+use crate::theme::Theme;
+use crate::ui::UI;
+
+/// Play gattai puzzles of `layout`, starting from `seed`. N starts a new one.
+/// Progress isn't saved.
+pub async fn play(layout: Layout, mut seed: Seed) {
+    let mut gattai = Gattai::generate(layout, seed);
+    let mut ui = UI::new();
+
+    let min_frame_time = 1. / 30.;
+
+    loop {
+        let frame_time = get_frame_time();
+
+        ui.update();
+
+        let typing = ui.insert_num().is_some();
+        if !typing && (is_key_pressed(KeyCode::N) || is_key_pressed(KeyCode::PageDown)) {
+            seed = Seed::random();
+            gattai = Gattai::generate(layout, seed);
+            ui.selected_cell = None;
+            ui.highlighted_cell = None;
+            ui.redraw = true;
+        }
+        let solved = if gattai.is_solved() { " - solved" } else { "" };
+        ui.header = format!("{layout} - seed {seed}{solved}");
+
+        ui.draw_gattai(&mut gattai);
+
+        // sleep for CPU's sake
+        if frame_time < min_frame_time {
+            let sleep_time = (min_frame_time - frame_time) * 1000.;
+            sleep(Duration::from_millis(sleep_time as u64));
+        }
+        next_frame().await;
+    }
+}
+
+impl UI {
+    /// Like `draw`, for the grids of a gattai puzzle on one square
+    pub fn draw_gattai(&mut self, gattai: &mut Gattai) {
+        clear_background(self.theme().bg);
+
+        if gattai.side() != self.side {
+            self.side = gattai.side();
+            self.redraw = true;
+        }
+
+        self.handle_gattai_input(gattai);
+
+        if self.redraw {
+            set_camera(&self.board_texture_cam);
+            clear_background(self.theme().bg);
+
+            self.draw_gattai_cells(gattai);
+            self.draw_gattai_squares(gattai);
+
+            set_default_camera();
+        }
+
+        self.draw_board_texture();
+        self.draw_header();
+    }
+
+    /// Place the typed number, checked on every grid the cell is in.
+    /// The offending cell is found on the grid that refused it.
+    fn handle_gattai_input(&mut self, gattai: &mut Gattai) {
+        let Some((index, num)) = self.insert_num() else {
+            return;
+        };
+        self.redraw = true;
+
+        let refused = gattai.grids_at(index).into_iter().find_map(|(g, cell)| {
+            let grid = gattai.grid(g);
+            let e = grid.placement_error(cell, Some(num))?;
+            Some((g, grid, cell, e))
+        });
+        match refused {
+            Some((g, grid, cell, e)) => {
+                self.highlight(&grid, cell, Some(num), e);
+                self.highlighted_cell = self.highlighted_cell.map(|i| gattai.grid_cell(g, i));
+            }
+            None if gattai.in_play(index) => {
+                gattai.cells[index] = Some(num);
+                self.highlighted_cell = None;
+            }
+            None => (),
+        }
+    }
+
+    fn draw_gattai_cells(&self, gattai: &Gattai) {
+        let cell_size = self.board_texture.texture.width() / gattai.side() as f32;
+
+        if let Some(index) = self.selected_cell
+            && gattai.in_play(index)
+        {
+            let (x, y) = gattai.coords(index);
+            draw_rectangle(
+                x as f32 * cell_size,
+                y as f32 * cell_size,
+                cell_size,
+                cell_size,
+                self.theme().selected_bg,
+            );
+        }
+
+        for index in 0..gattai.size() {
+            if let Some(num) = gattai.cells[index] {
+                let (x, y) = gattai.coords(index);
+                let cell = Rect::new(
+                    x as f32 * cell_size,
+                    y as f32 * cell_size,
+                    cell_size,
+                    cell_size,
+                );
+                self.draw_cell_num(cell, num, Some(index) == self.highlighted_cell);
+            }
+        }
+    }
+
+    /// Thin lines around every cell in play, and thick ones between segments
+    /// and around the grids. Segments line up across grids, so they're the same
+    /// 3×3 squares everywhere.
+    fn draw_gattai_squares(&self, gattai: &Gattai) {
+        let &Theme {
+            square_thick,
+            square_color: square,
+            cell_thick,
+            cell_border: cell_color,
+            border_thick,
+            border_color,
+            ..
+        } = self.theme();
+        let [square_thick, cell_thick, border_thick] =
+            [square_thick, cell_thick, border_thick].map(|thick| thick * self.dpi);
+
+        let side = gattai.side();
+        let cell_size = self.board_texture.texture.width() / side as f32;
+        let thick = square_thick * 2.;
+        // cells off the board aren't in play, so the grids get an outline too
+        let in_play = |x: usize, y: usize| x < side && y < side && gattai.in_play((x, y));
+
+        for index in 0..gattai.size() {
+            let (x, y) = gattai.coords(index);
+            let (left, top) = (x as f32 * cell_size, y as f32 * cell_size);
+            let (right, bottom) = (left + cell_size, top + cell_size);
+            let here = in_play(x, y);
+            if here {
+                draw_rectangle_lines(left, top, cell_size, cell_size, cell_thick, cell_color);
+            }
+
+            let edges = [
+                (
+                    in_play(x + 1, y),
+                    (x + 1) % 3 == 0,
+                    (right, top, right, bottom),
+                ),
+                (
+                    in_play(x, y + 1),
+                    (y + 1) % 3 == 0,
+                    (left, bottom, right, bottom),
+                ),
+            ];
+            for (next, between_segs, (x1, y1, x2, y2)) in edges {
+                let outline = here != next;
+                if !(outline || here && between_segs) {
+                    continue;
+                }
+                let (thick, color) = if outline {
+                    (border_thick, border_color)
+                } else {
+                    (thick, square)
+                };
+                // run on into the corners, so lines meet
+                let (dx, dy) = if x1 == x2 {
+                    (0., square_thick)
+                } else {
+                    (square_thick, 0.)
+                };
+                draw_line(x1 - dx, y1 - dy, x2 + dx, y2 + dy, thick, color);
+            }
+            // the top and left edges of the whole square
+            for (outline, (x1, y1, x2, y2)) in [
+                (here && x == 0, (left, top, left, bottom)),
+                (here && y == 0, (left, top, right, top)),
+            ] {
+                if outline {
+                    draw_line(x1, y1, x2, y2, border_thick, border_color);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use macroquad::prelude::*;
+
+/// Where everything goes on screen, in logical pixels like `screen_width`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Layout {
+    /// the space around and between the parts, sandwich sums are written in it
+    pub padding: f32,
+    /// a line of text over everything else
+    pub header: Rect,
+    pub board: Rect,
+    /// buttons to browse puzzles and change the theme
+    pub controls: Rect,
+    pub pad: Rect,
+}
+
+/// the share of the window's longer side the board takes at most next to the panel
+const BOARD_SHARE: f32 = 0.68;
+
+impl Layout {
+    /// Everything fitted into a window `screen` large. Landscape windows get a
+    /// side panel right of the board, with the controls over the number pad, and
+    /// portrait ones get it under the board. Without `panel` the board gets all
+    /// the room under the header.
+    pub fn new(screen: Vec2, panel: bool) -> Self {
+        let short = screen.min_element();
+        let padding = (short * 0.03).clamp(6., 32.);
+        let header_height = (short * 0.05).clamp(18., 40.);
+
+        let header = Rect::new(padding, padding, screen.x - padding * 2., header_height);
+        let top = header.bottom() + padding / 2.;
+        let area = Rect::new(
+            padding,
+            top,
+            screen.x - padding * 2.,
+            screen.y - top - padding,
+        );
+
+        let mut layout = Layout {
+            padding,
+            header,
+            ..Default::default()
+        };
+        if !panel {
+            let side = area.w.min(area.h).max(0.);
+            let x = area.x + (area.w - side) / 2.;
+            layout.board = Rect::new(x, area.y, side, side);
+            return layout;
+        }
+
+        let panel = if area.w >= area.h {
+            let side = area.h.min((area.w - padding) * BOARD_SHARE).max(0.);
+            layout.board = Rect::new(area.x, area.y, side, side);
+            let x = layout.board.right() + padding;
+            Rect::new(x, area.y, area.right() - x, side)
+        } else {
+            let side = area.w.min((area.h - padding) * BOARD_SHARE).max(0.);
+            layout.board = Rect::new(area.x + (area.w - side) / 2., area.y, side, side);
+            let y = layout.board.bottom() + padding;
+            Rect::new(area.x, y, area.w, area.bottom() - y)
+        };
+
+        // one row of controls, as tall as a wide button is
+        let controls_height = (panel.w / 6. * 0.6).min(panel.h * 0.2);
+        layout.controls = Rect::new(panel.x, panel.y, panel.w, controls_height);
+        let y = layout.controls.bottom() + padding;
+        layout.pad = Rect::new(panel.x, y, panel.w, (panel.bottom() - y).max(0.));
+        layout
+    }
+}
@@ -1,24 +1,36 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod board;
+mod cli;
+mod collection;
+mod controls;
+mod daily;
 mod draw;
-mod generate;
-mod test;
+mod editor;
+mod gattai;
+mod layout;
+mod mode;
+mod pad;
+mod scene;
+mod settings;
+mod stats;
+mod storage;
 mod theme;
 mod ui;
 
-use std::{thread::sleep, time::Duration};
+use std::{path::Path, thread::sleep, time::Duration};
 
-use generate::Strategy;
+use collection::{Collection, Rules};
 use macroquad::{miniquad::conf::Platform, prelude::*};
-use ui::UI;
+use mode::Mode;
+use scene::{App, Setup, Variant};
+use sudoru_core::{Board, Difficulty, Seed, Shape, gattai::Layout};
 
 pub const SAMPLE_COUNT: i32 = 2;
 
 fn app() -> Conf {
     Conf {
         window_title: "Sudoru".to_owned(),
-        window_width: 800,
+        window_width: 1100,
         window_height: 800,
         window_resizable: true,
         sample_count: SAMPLE_COUNT,
@@ -31,21 +43,171 @@ fn app() -> Conf {
     }
 }
 
-#[macroquad::main(app)]
-async fn main() {
-    let gen_strategy = Strategy::TryRandomSparse;
-    let mut board = generate::generate_board(gen_strategy);
+fn open_collection(path: &Path, shuffle: bool, rules: &Rules) -> Option<Collection> {
+    match Collection::open(path, shuffle, rules) {
+        Ok(collection) => Some(collection),
+        Err(e) => {
+            eprintln!("failed to open {}: {e}", path.display());
+            None
+        }
+    }
+}
 
-    let mut ui = UI::new();
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-    let min_frame_time = 1. / 30.;
+    // subcommands run headless, anything else opens the game
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
 
-    loop {
-        let frame_time = get_frame_time();
+    let options = match Options::parse(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{}", cli::USAGE);
+            std::process::exit(1);
+        }
+    };
+    macroquad::Window::from_config(app(), game(options));
+}
+
+/// what the game's arguments ask for
+#[derive(Default)]
+struct Options {
+    seed: Option<Seed>,
+    shape: Shape,
+    diagonals: bool,
+    killer: bool,
+    jigsaw: bool,
+    comparison: bool,
+    layout: Option<Layout>,
+    edit_path: Option<String>,
+    constraints: String,
+    collection_path: Option<String>,
+    daily: bool,
+    shuffle: bool,
+}
+
+impl Options {
+    /// Options that can't be read are reported and left out,
+    /// while an option that doesn't exist fails, it's likely a typo.
+    fn parse(args: Vec<String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => match args.next().map(|code| code.parse()) {
+                    Some(Ok(code)) => options.seed = Some(code),
+                    Some(Err(e)) => eprintln!("--seed: {e}"),
+                    None => eprintln!("--seed needs a seed code"),
+                },
+                "--size" => match args.next().map(|size| size.parse()) {
+                    Some(Ok(size)) => options.shape = size,
+                    Some(Err(e)) => eprintln!("--size: {e}"),
+                    None => eprintln!("--size needs a board size"),
+                },
+                "--diagonal" => options.diagonals = true,
+                "--killer" => options.killer = true,
+                "--jigsaw" => options.jigsaw = true,
+                "--comparison" => options.comparison = true,
+                "--gattai" => match args.next().map(|layout| layout.parse()) {
+                    Some(Ok(name)) => options.layout = Some(name),
+                    Some(Err(e)) => eprintln!("--gattai: {e}"),
+                    None => eprintln!("--gattai needs a layout"),
+                },
+                "--edit" => match args.next() {
+                    Some(path) => options.edit_path = Some(path),
+                    None => eprintln!("--edit needs a file"),
+                },
+                "--constraints" => match args.next() {
+                    Some(list) => options.constraints = list,
+                    None => eprintln!("--constraints needs a list of constraints"),
+                },
+                "--daily" => options.daily = true,
+                "--shuffle" => options.shuffle = true,
+                _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+                _ => options.collection_path = Some(arg),
+            }
+        }
+        Ok(options)
+    }
+}
+
+async fn game(options: Options) {
+    let Options {
+        seed,
+        shape,
+        diagonals,
+        killer,
+        jigsaw,
+        comparison,
+        layout,
+        edit_path,
+        constraints,
+        collection_path,
+        daily,
+        shuffle,
+    } = options;
+    let difficulty = Difficulty::Medium;
+
+    if let Some(layout) = layout {
+        gattai::play(layout, seed.unwrap_or_else(Seed::random)).await;
+        return;
+    }
+
+    let rules = Rules {
+        diagonals,
+        constraints: constraints.clone(),
+    };
+    let constraints = cli::parse_constraints(&constraints, shape.size()).unwrap_or_else(|e| {
+        eprintln!("--constraints: {e}");
+        Vec::new()
+    });
+    let empty = Board {
+        diagonals,
+        constraints,
+        ..Board::with_shape(shape)
+    };
+    if let Some(path) = edit_path {
+        editor::edit(path.into(), empty).await;
+        return;
+    }
+
+    let variant = if killer {
+        Variant::Killer
+    } else if jigsaw {
+        Variant::Jigsaw
+    } else if comparison {
+        Variant::Comparison
+    } else {
+        Variant::Classic
+    };
+    let setup = Setup {
+        empty,
+        variant,
+        difficulty,
+        seed,
+        unsolvable: false,
+    };
+    let mut app = App::new(setup, shuffle, rules);
+
+    // a collection file can be passed as an argument, dropped onto the window or pasted,
+    // and the game starts right away when it's clear what to play
+    let collection =
+        collection_path.and_then(|path| open_collection(Path::new(&path), shuffle, &app.rules));
+    if let Some(collection) = collection {
+        app.start(Mode::Collection(collection));
+    } else if daily {
+        app.start(Mode::daily());
+    } else if app.setup.seed.is_some() {
+        let mode = app.setup.mode();
+        app.start(mode);
+    }
 
-        ui.update();
+    let min_frame_time = 1. / 30.;
 
-        ui.draw(&mut board);
+    while app.frame() {
+        let frame_time = get_frame_time();
 
         // sleep for CPU's sake
         if frame_time < min_frame_time {
@@ -0,0 +1,168 @@
+use sudoru_core::{Board, Difficulty, Seed, Shape, Strategy, generate};
+
+use crate::{
+    collection::{Collection, Status},
+    daily::{self, Dailies},
+};
+
+/// Where the puzzles being played come from
+#[allow(clippy::large_enum_variant)]
+pub enum Mode {
+    /// freshly generated puzzles, N starts a new one
+    Random {
+        /// the shape and rules of the puzzles
+        empty: Board,
+        /// Killer Sudoku, with cages
+        killer: bool,
+        /// Jigsaw Sudoku, a new layout of regions for every puzzle
+        jigsaw: bool,
+        /// Greater-than Sudoku, comparisons between the cells of every segment
+        comparison: bool,
+        difficulty: Difficulty,
+        seed: Seed,
+    },
+    Collection(Collection),
+    Daily(Dailies),
+}
+
+impl Mode {
+    pub fn daily() -> Self {
+        Self::Daily(Dailies::load(daily::today()))
+    }
+
+    /// The board to play, including any saved progress.
+    /// `None` if no random puzzle follows the rules.
+    pub fn board(&self) -> Option<Board> {
+        match self {
+            Self::Random {
+                empty,
+                killer,
+                jigsaw,
+                comparison,
+                difficulty,
+                seed,
+            } => {
+                let strategy = if *killer {
+                    Strategy::Killer {
+                        difficulty: *difficulty,
+                        givens: true,
+                    }
+                } else if *jigsaw {
+                    Strategy::Jigsaw(*difficulty)
+                } else if *comparison {
+                    Strategy::Comparison(*difficulty)
+                } else {
+                    Strategy::Unique(*difficulty)
+                };
+                generate::generate_from(empty, strategy, *seed)
+            }
+            Self::Collection(collection) => Some(collection.board()),
+            Self::Daily(dailies) => Some(dailies.board()),
+        }
+    }
+
+    pub fn next(&mut self) -> Option<Board> {
+        match self {
+            Self::Random { seed, .. } => {
+                *seed = Seed::random();
+                self.board()
+            }
+            Self::Collection(collection) => Some(collection.next()),
+            Self::Daily(dailies) => Some(dailies.next()),
+        }
+    }
+
+    /// `None` if there's nothing to go back to
+    pub fn prev(&mut self) -> Option<Board> {
+        match self {
+            Self::Random { .. } => None,
+            Self::Collection(collection) => Some(collection.prev()),
+            Self::Daily(dailies) => Some(dailies.prev()),
+        }
+    }
+
+    /// remember progress on the current board
+    pub fn record(&mut self, board: &Board) {
+        match self {
+            Self::Random { .. } => {}
+            Self::Collection(collection) => collection.record(board),
+            Self::Daily(dailies) => dailies.record(board),
+        }
+    }
+
+    /// what solved puzzles are counted as in the statistics
+    pub fn kind(&self) -> String {
+        match self {
+            Self::Random {
+                empty, difficulty, ..
+            } => format!("{} {difficulty}", empty.shape),
+            Self::Collection(collection) => collection.name(),
+            Self::Daily(_) => "daily".to_owned(),
+        }
+    }
+
+    pub fn header(&self) -> String {
+        let status = |status| match status {
+            Status::Unsolved => "",
+            Status::InProgress => " - in progress",
+            Status::Solved => " - solved",
+        };
+
+        match self {
+            Self::Random {
+                empty,
+                killer,
+                jigsaw,
+                comparison,
+                difficulty,
+                seed,
+            } => {
+                let mut name = String::new();
+                if empty.shape != Shape::STANDARD {
+                    name += &format!("{} ", empty.shape);
+                }
+                if empty.diagonals {
+                    name += "diagonal ";
+                }
+                if *killer {
+                    name += "killer ";
+                }
+                if *jigsaw {
+                    name += "jigsaw ";
+                }
+                if *comparison {
+                    name += "greater-than ";
+                }
+                let mut names: Vec<&str> = empty.constraints.iter().map(|c| c.name()).collect();
+                // several thermometers or sandwiches are named once
+                names.dedup();
+                for constraint in names {
+                    name += &format!("{constraint} ");
+                }
+                format!("{name}{difficulty} - seed {seed}")
+            }
+            Self::Collection(collection) => format!(
+                "{} {}/{} ({} solved){}   {}",
+                collection.name(),
+                collection.index + 1,
+                collection.len(),
+                collection.solved_count(),
+                status(collection.status[collection.index]),
+                collection.current().comment,
+            ),
+            Self::Daily(dailies) => {
+                let date = dailies.daily.date;
+                let today = match dailies.status(dailies.today) {
+                    Status::Solved => "today done",
+                    _ => "today open",
+                };
+                format!(
+                    "daily {date} ({}){}   streak {}, {today}",
+                    dailies.daily.difficulty,
+                    status(dailies.status(date)),
+                    dailies.streak(),
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use macroquad::prelude::*;
+use sudoru_core::board::{CELL_STR, COLORS};
+
+use crate::ui::UI;
+
+/// what typed or clicked numbers do to the selected cell
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Entry {
+    #[default]
+    Digit,
+    Corner,
+    Center,
+    Color,
+}
+
+impl Entry {
+    const ALL: [Entry; 4] = [Entry::Digit, Entry::Corner, Entry::Center, Entry::Color];
+
+    fn label(self) -> &'static str {
+        match self {
+            Entry::Digit => "Digit",
+            Entry::Corner => "Corner",
+            Entry::Center => "Center",
+            Entry::Color => "Color",
+        }
+    }
+}
+
+/// a button of the number pad
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Button {
+    Num(u8),
+    Entry(Entry),
+    Undo,
+    Erase,
+    Hint,
+}
+
+impl Button {
+    fn label(self) -> &'static str {
+        match self {
+            Button::Num(num) => CELL_STR[num as usize - 1],
+            Button::Entry(entry) => entry.label(),
+            Button::Undo => "Undo",
+            Button::Erase => "Erase",
+            Button::Hint => "Hint",
+        }
+    }
+}
+
+/// how much of a button's size is left between it and the next
+const GAP: f32 = 0.06;
+
+impl UI {
+    /// The buttons of the number pad, where they are on screen. The entry modes
+    /// are on top, the numbers of the board or the colors in a square under them,
+    /// and undo, erase and hint at the bottom.
+    pub fn pad_buttons(&self) -> Vec<(Rect, Button)> {
+        if !self.panel {
+            return Vec::new();
+        }
+        let count = if self.entry == Entry::Color {
+            COLORS as usize
+        } else {
+            self.side
+        };
+        let area = self.layout.pad;
+        // as many columns as make the largest buttons, a row under a wide board
+        let size = |cols: usize| {
+            let rows = count.div_ceil(cols) + 2;
+            (area.w / cols as f32).min(area.h / rows as f32)
+        };
+        let cols = (1..=count)
+            .max_by(|&a, &b| size(a).total_cmp(&size(b)))
+            .unwrap_or(1);
+        let (rows, unit) = (count.div_ceil(cols) + 2, size(cols));
+        let width = unit * cols as f32;
+        let left = area.x + (area.w - width) / 2.;
+        let row_y = |row: usize| area.y + unit * row as f32;
+
+        // a row of buttons as wide as the numbers
+        let spread = |buttons: &[Button], y: f32| -> Vec<(Rect, Button)> {
+            let w = width / buttons.len() as f32;
+            buttons
+                .iter()
+                .enumerate()
+                .map(|(k, &button)| (Rect::new(left + w * k as f32, y, w, unit), button))
+                .collect()
+        };
+
+        let mut buttons = spread(&Entry::ALL.map(Button::Entry), row_y(0));
+        for k in 0..count {
+            let (x, y) = (k % cols, k / cols);
+            let rect = Rect::new(left + unit * x as f32, row_y(y + 1), unit, unit);
+            buttons.push((rect, Button::Num(k as u8 + 1)));
+        }
+        buttons.extend(spread(
+            &[Button::Undo, Button::Erase, Button::Hint],
+            row_y(rows - 1),
+        ));
+
+        for (rect, _) in &mut buttons {
+            let gap = unit * GAP;
+            *rect = Rect::new(
+                rect.x + gap,
+                rect.y + gap,
+                rect.w - gap * 2.,
+                rect.h - gap * 2.,
+            );
+        }
+        buttons
+    }
+
+    /// the button clicked this frame
+    pub fn pad_clicked(&self) -> Option<Button> {
+        if !is_mouse_button_pressed(MouseButton::Left) {
+            return None;
+        }
+        let mouse: Vec2 = mouse_position().into();
+        self.pad_buttons()
+            .into_iter()
+            .find(|(rect, _)| rect.contains(mouse))
+            .map(|(_, button)| button)
+    }
+
+    /// the number pad beside or under the board, the current entry mode shaded
+    pub fn draw_pad(&self) {
+        let theme = self.theme();
+        for (rect, button) in self.pad_buttons() {
+            let fill = match button {
+                Button::Num(color) if self.entry == Entry::Color => {
+                    Some(theme.palette[color as usize - 1])
+                }
+                Button::Entry(entry) if entry == self.entry => Some(theme.selected_bg),
+                _ => None,
+            };
+            self.draw_button(rect, button.label(), fill);
+        }
+    }
+
+    /// a button of the side panel, filled with `fill` if it has one
+    pub fn draw_button(&self, rect: Rect, label: &str, fill: Option<Color>) {
+        let theme = self.theme();
+        let Rect { x, y, w, h } = rect;
+        if let Some(fill) = fill {
+            draw_rectangle(x, y, w, h, fill);
+        }
+        draw_rectangle_lines(x, y, w, h, theme.cell_thick * 2., theme.cell_border);
+
+        // shrunk until the label fits
+        let mut font_size = (h * 0.5) as u16;
+        let mut size = measure_text(label, None, font_size, 1.);
+        while size.width > w * 0.9 && font_size > 1 {
+            font_size -= 1;
+            size = measure_text(label, None, font_size, 1.);
+        }
+        let center = rect.center();
+        draw_text_ex(
+            label,
+            center.x - size.width / 2.,
+            center.y - size.height / 2. + size.offset_y,
+            TextParams {
+                font_size,
+                color: theme.cell_fg,
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,488 @@
+use macroquad::prelude::*;
+use sudoru_core::{Board, Difficulty, Seed, Shape};
+
+use crate::{
+    collection::Rules,
+    controls::Control,
+    daily,
+    mode::Mode,
+    settings::Settings,
+    stats::{self, Stats},
+    ui::UI,
+};
+
+/// how long the fade into another scene takes, in seconds
+const FADE: f32 = 0.25;
+
+/// What's on screen, each scene with its own input
+#[derive(Clone, Debug, PartialEq)]
+pub enum Scene {
+    Menu,
+    /// picking the rules of a new random game
+    Setup,
+    Game,
+    /// the game with its board hidden and its time stopped
+    Pause,
+    /// opened from the menu, or from the pause menu when `paused`
+    Settings {
+        paused: bool,
+    },
+    /// lines about the solved puzzles, worked out when the scene opens
+    Statistics(Vec<String>),
+    /// the puzzle was just solved, `best` if it was the fastest of its kind
+    Victory {
+        best: bool,
+    },
+}
+
+/// the rules of the puzzles random games can have, besides a board's constraints
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Classic,
+    Killer,
+    Jigsaw,
+    Comparison,
+}
+
+impl Variant {
+    const ALL: [Variant; 4] = [
+        Variant::Classic,
+        Variant::Killer,
+        Variant::Jigsaw,
+        Variant::Comparison,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Variant::Classic => "classic",
+            Variant::Killer => "killer",
+            Variant::Jigsaw => "jigsaw",
+            Variant::Comparison => "greater-than",
+        }
+    }
+}
+
+/// what the next random game will be
+pub struct Setup {
+    /// the shape, diagonals and constraints of the puzzles
+    pub empty: Board,
+    pub variant: Variant,
+    pub difficulty: Difficulty,
+    /// the seed of the first game, from `--seed`, after that they're random
+    pub seed: Option<Seed>,
+    /// no puzzle followed the rules the last time a game was started
+    pub unsolvable: bool,
+}
+
+impl Setup {
+    /// random games with these rules, the first one from `seed` if it was given
+    pub fn mode(&mut self) -> Mode {
+        Mode::Random {
+            empty: self.empty.clone(),
+            killer: self.variant == Variant::Killer,
+            jigsaw: self.variant == Variant::Jigsaw,
+            comparison: self.variant == Variant::Comparison,
+            difficulty: self.difficulty,
+            seed: self.seed.take().unwrap_or_else(Seed::random),
+        }
+    }
+}
+
+/// the puzzles being played and how long the current one has taken
+pub struct Game {
+    pub mode: Mode,
+    pub board: Board,
+    /// seconds spent on `board`, not counting pauses
+    pub time: f32,
+}
+
+/// The scene on screen and everything the scenes share
+pub struct App {
+    pub ui: UI,
+    pub scene: Scene,
+    /// `None` until a game starts
+    pub game: Option<Game>,
+    pub setup: Setup,
+    pub settings: Settings,
+    pub stats: Stats,
+    /// play collections as shuffled equivalents
+    pub shuffle: bool,
+    /// added to the puzzles of opened collections
+    pub rules: Rules,
+    /// seconds left of the fade into the scene
+    fade: f32,
+    /// draw the board again, it may have changed while another scene was on
+    redraw: bool,
+    running: bool,
+}
+
+impl App {
+    pub fn new(setup: Setup, shuffle: bool, rules: Rules) -> Self {
+        let settings = Settings::load();
+        let mut ui = UI::new();
+        ui.panel = settings.panel;
+        if settings.dark {
+            ui.cycle_theme();
+        }
+        Self {
+            ui,
+            scene: Scene::Menu,
+            game: None,
+            setup,
+            settings,
+            stats: Stats::load(),
+            shuffle,
+            rules,
+            fade: 0.,
+            redraw: false,
+            running: true,
+        }
+    }
+
+    /// Play the puzzles of `mode`, from its current one.
+    /// Random games whose rules no puzzle follows go back to the setup instead.
+    pub fn start(&mut self, mode: Mode) {
+        let Some(board) = mode.board() else {
+            self.setup.unsolvable = true;
+            self.go(Scene::Setup);
+            return;
+        };
+        self.game = Some(Game {
+            mode,
+            board,
+            time: 0.,
+        });
+        self.ui.history.clear();
+        self.ui.selected_cell = None;
+        self.ui.highlighted_cell = None;
+        self.go(Scene::Game);
+    }
+
+    fn go(&mut self, scene: Scene) {
+        self.scene = scene;
+        self.fade = FADE;
+        self.redraw = true;
+    }
+
+    /// Update and draw the active scene for a frame, `false` once the player quit.
+    /// Files dropped onto the window or pasted open in a new game from any scene.
+    pub fn frame(&mut self) -> bool {
+        // clicks on menus aren't meant for the board
+        if self.scene == Scene::Game {
+            self.ui.update();
+        } else {
+            self.ui.redraw = false;
+            self.ui.update_layout();
+        }
+        self.ui.redraw |= std::mem::take(&mut self.redraw);
+
+        if let Some(path) = self.ui.dropped_file().or_else(|| self.ui.pasted_file())
+            && let Some(opened) = crate::open_collection(&path, self.shuffle, &self.rules)
+        {
+            self.start(Mode::Collection(opened));
+        }
+
+        let next = match self.scene.clone() {
+            Scene::Menu => self.menu(),
+            Scene::Setup => self.new_game(),
+            Scene::Game => self.play(),
+            Scene::Pause => self.pause(),
+            Scene::Settings { paused } => self.change_settings(paused),
+            Scene::Statistics(lines) => self.statistics(&lines),
+            Scene::Victory { best } => self.victory(best),
+        };
+        if let Some(scene) = next {
+            self.go(scene);
+        }
+
+        // the new scene comes out of the background
+        if self.fade > 0. {
+            let mut color = self.ui.theme().bg;
+            color.a = self.fade / FADE;
+            draw_rectangle(0., 0., screen_width(), screen_height(), color);
+            self.fade -= get_frame_time();
+        }
+        self.running
+    }
+
+    fn menu(&mut self) -> Option<Scene> {
+        clear_background(self.ui.theme().bg);
+        let mut items = vec!["New game", "Daily puzzle", "Statistics", "Settings", "Quit"];
+        if self.game.is_some() {
+            items.insert(0, "Continue");
+        }
+        if self.game.is_some() && is_key_pressed(KeyCode::Escape) {
+            return Some(Scene::Game);
+        }
+
+        match menu(&self.ui, "Sudoru", &[], &items).map(|k| items[k]) {
+            Some("Continue") => Some(Scene::Game),
+            Some("New game") => Some(Scene::Setup),
+            Some("Daily puzzle") => {
+                self.start(Mode::daily());
+                None
+            }
+            Some("Statistics") => Some(Scene::Statistics(self.statistics_lines())),
+            Some("Settings") => Some(Scene::Settings { paused: false }),
+            Some("Quit") => {
+                self.running = false;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Size, rules and difficulty of a new random game. Changing the size leaves
+    /// constraints on cells behind, they only fit the board they were made for.
+    fn new_game(&mut self) -> Option<Scene> {
+        if is_key_pressed(KeyCode::Escape) {
+            return Some(Scene::Menu);
+        }
+        clear_background(self.ui.theme().bg);
+        let setup = &mut self.setup;
+        let on_off = |on: bool| if on { "on" } else { "off" };
+        let items = [
+            format!("Size: {}", setup.empty.shape),
+            format!("Rules: {}", setup.variant.name()),
+            format!("Diagonals: {}", on_off(setup.empty.diagonals)),
+            format!("Difficulty: {}", setup.difficulty),
+            "Start".to_owned(),
+            "Back".to_owned(),
+        ];
+
+        let lines = if setup.unsolvable {
+            vec!["no puzzle follows these rules".to_owned()]
+        } else {
+            Vec::new()
+        };
+
+        let item = menu(&self.ui, "New game", &lines, &items)?;
+        setup.unsolvable = false;
+        match item {
+            0 => {
+                let shape = next_of(&Shape::ALL, setup.empty.shape);
+                setup.empty = Board {
+                    diagonals: setup.empty.diagonals,
+                    ..Board::with_shape(shape)
+                };
+            }
+            1 => setup.variant = next_of(&Variant::ALL, setup.variant),
+            2 => setup.empty.diagonals = !setup.empty.diagonals,
+            3 => setup.difficulty = next_of(&Difficulty::ALL, setup.difficulty),
+            4 => {
+                let mode = setup.mode();
+                self.start(mode);
+            }
+            _ => return Some(Scene::Menu),
+        }
+        None
+    }
+
+    /// the puzzle with the side panel, Escape or Pause pauses it
+    fn play(&mut self) -> Option<Scene> {
+        let Some(game) = &mut self.game else {
+            return Some(Scene::Menu);
+        };
+        if is_key_pressed(KeyCode::Escape) || self.ui.control_clicked() == Some(Control::Pause) {
+            return Some(Scene::Pause);
+        }
+
+        if self.ui.browse(&mut game.mode, &mut game.board) {
+            game.time = 0.;
+        }
+        game.time += get_frame_time();
+        self.ui.header += &format!(" - {}", stats::format_time(game.time));
+
+        let before = game.board.clone();
+        self.ui.draw(&mut game.board);
+
+        if game.board != before {
+            game.mode.record(&game.board);
+            if game.board.is_solved() && !before.is_solved() {
+                let best = self.stats.solved(&game.mode.kind(), game.time);
+                return Some(Scene::Victory { best });
+            }
+        }
+        None
+    }
+
+    fn pause(&mut self) -> Option<Scene> {
+        if is_key_pressed(KeyCode::Escape) {
+            return Some(Scene::Game);
+        }
+        clear_background(self.ui.theme().bg);
+        let time = self.game.as_ref().map(|game| game.time).unwrap_or_default();
+        let lines = [format!("time {}", stats::format_time(time))];
+
+        match menu(
+            &self.ui,
+            "Paused",
+            &lines,
+            &["Resume", "Settings", "Main menu"],
+        )? {
+            0 => Some(Scene::Game),
+            1 => Some(Scene::Settings { paused: true }),
+            _ => Some(Scene::Menu),
+        }
+    }
+
+    fn change_settings(&mut self, paused: bool) -> Option<Scene> {
+        let back = if paused { Scene::Pause } else { Scene::Menu };
+        if is_key_pressed(KeyCode::Escape) {
+            return Some(back);
+        }
+        clear_background(self.ui.theme().bg);
+        // T changes the theme while playing too
+        self.settings.dark = self.ui.is_dark();
+        let items = [
+            format!(
+                "Theme: {}",
+                if self.settings.dark { "dark" } else { "light" }
+            ),
+            format!(
+                "Number pad: {}",
+                if self.settings.panel {
+                    "shown"
+                } else {
+                    "hidden"
+                }
+            ),
+            "Back".to_owned(),
+        ];
+
+        match menu(&self.ui, "Settings", &[], &items)? {
+            0 => {
+                self.ui.cycle_theme();
+                self.settings.dark = self.ui.is_dark();
+            }
+            1 => {
+                self.settings.panel = !self.settings.panel;
+                self.ui.set_panel(self.settings.panel);
+            }
+            _ => {
+                self.settings.save();
+                return Some(back);
+            }
+        }
+        self.settings.save();
+        None
+    }
+
+    /// every kind of puzzle solved so far, and the daily streak
+    fn statistics_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .stats
+            .records
+            .iter()
+            .map(|(kind, record)| {
+                format!(
+                    "{kind}: {} solved, best {}",
+                    record.solved,
+                    stats::format_time(record.best)
+                )
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push("nothing solved yet".to_owned());
+        }
+        let streak = daily::Dailies::load(daily::today()).streak();
+        lines.push(format!("daily streak: {streak}"));
+        lines
+    }
+
+    fn statistics(&mut self, lines: &[String]) -> Option<Scene> {
+        if is_key_pressed(KeyCode::Escape) {
+            return Some(Scene::Menu);
+        }
+        clear_background(self.ui.theme().bg);
+        menu(&self.ui, "Statistics", lines, &["Back"]).map(|_| Scene::Menu)
+    }
+
+    /// the solved board behind the time it took
+    fn victory(&mut self, best: bool) -> Option<Scene> {
+        let Some(game) = &mut self.game else {
+            return Some(Scene::Menu);
+        };
+        self.ui.show(&game.board);
+        let mut veil = self.ui.theme().bg;
+        veil.a = 0.8;
+        draw_rectangle(0., 0., screen_width(), screen_height(), veil);
+
+        let mut lines = vec![format!("time {}", stats::format_time(game.time))];
+        if best {
+            lines.push("the best time yet".to_owned());
+        }
+        match menu(&self.ui, "Solved!", &lines, &["Next puzzle", "Main menu"])? {
+            0 => {
+                if let Some(board) = game.mode.next() {
+                    game.board = board;
+                }
+                game.time = 0.;
+                self.ui.history.clear();
+                self.ui.selected_cell = None;
+                self.ui.highlighted_cell = None;
+                Some(Scene::Game)
+            }
+            _ => Some(Scene::Menu),
+        }
+    }
+}
+
+/// the item after `current` in `all`, the first after the last
+fn next_of<T: Copy + PartialEq>(all: &[T], current: T) -> T {
+    let k = all.iter().position(|&item| item == current).unwrap_or(0);
+    all[(k + 1) % all.len()]
+}
+
+/// A title, lines of text and a column of buttons in the middle of the window.
+/// The index of the button clicked this frame, if any.
+fn menu(ui: &UI, title: &str, lines: &[String], items: &[impl AsRef<str>]) -> Option<usize> {
+    let screen = ui.screen_size;
+    let unit = (screen.min_element() * 0.07).clamp(24., 56.);
+    let width = (screen.x * 0.8).min(unit * 8.);
+    let height = unit * (1.5 + lines.len() as f32 * 0.7 + items.len() as f32 * 1.2);
+    let x = (screen.x - width) / 2.;
+    let mut y = ((screen.y - height) / 2.).max(0.);
+    let color = ui.theme().cell_fg;
+
+    draw_centered(title, vec2(screen.x / 2., y + unit * 0.6), unit, color);
+    y += unit * 1.5;
+    for line in lines {
+        draw_centered(
+            line,
+            vec2(screen.x / 2., y + unit * 0.35),
+            unit * 0.5,
+            color,
+        );
+        y += unit * 0.7;
+    }
+
+    let mouse: Vec2 = mouse_position().into();
+    let mut clicked = None;
+    for (k, item) in items.iter().enumerate() {
+        let rect = Rect::new(x, y + unit * 0.1, width, unit);
+        let hovered = rect.contains(mouse);
+        let fill = hovered.then_some(ui.theme().selected_bg);
+        ui.draw_button(rect, item.as_ref(), fill);
+        if hovered && is_mouse_button_pressed(MouseButton::Left) {
+            clicked = Some(k);
+        }
+        y += unit * 1.2;
+    }
+    clicked
+}
+
+fn draw_centered(text: &str, center: Vec2, font_size: f32, color: Color) {
+    let font_size = font_size as u16;
+    let size = measure_text(text, None, font_size, 1.);
+    draw_text_ex(
+        text,
+        center.x - size.width / 2.,
+        center.y - size.height / 2. + size.offset_y,
+        TextParams {
+            font_size,
+            color,
+            ..Default::default()
+        },
+    );
+}
@@ -0,0 +1,48 @@
+use crate::storage;
+
+const FILE: &str = "settings.txt";
+
+/// What the player picked in the settings, remembered between runs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    pub dark: bool,
+    /// the controls and number pad next to the board
+    pub panel: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            dark: false,
+            panel: true,
+        }
+    }
+}
+
+impl Settings {
+    /*
+    dark true
+    panel false
+    */
+    pub fn load() -> Self {
+        let mut settings = Self::default();
+        for line in storage::read(FILE).unwrap_or_default().lines() {
+            let Some((key, Ok(value))) = line.split_once(' ').map(|(k, v)| (k, v.parse())) else {
+                continue;
+            };
+            match key {
+                "dark" => settings.dark = value,
+                "panel" => settings.panel = value,
+                _ => (),
+            }
+        }
+        settings
+    }
+
+    pub fn save(&self) {
+        let text = format!("dark {}\npanel {}\n", self.dark, self.panel);
+        if let Err(e) = storage::write(FILE, &text) {
+            eprintln!("failed to save settings: {e}");
+        }
+    }
+}
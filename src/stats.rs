@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use crate::storage;
+
+const FILE: &str = "stats.txt";
+
+/// How many puzzles of a kind were solved, and the fastest
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Record {
+    pub solved: u32,
+    /// in seconds
+    pub best: f32,
+}
+
+/// Solved puzzles for every kind of puzzle, like `9x9 medium` or `daily`
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub records: BTreeMap<String, Record>,
+}
+
+impl Stats {
+    /*
+    12 185.5 9x9 medium
+    3 240 daily
+    */
+    pub fn load() -> Self {
+        let mut stats = Self::default();
+        for line in storage::read(FILE).unwrap_or_default().lines() {
+            let mut words = line.splitn(3, ' ');
+            let (Some(Ok(solved)), Some(Ok(best)), Some(kind)) = (
+                words.next().map(str::parse),
+                words.next().map(str::parse),
+                words.next(),
+            ) else {
+                continue;
+            };
+            stats
+                .records
+                .insert(kind.to_owned(), Record { solved, best });
+        }
+        stats
+    }
+
+    /// count a puzzle of `kind` solved in `seconds`, `true` if that's the best time yet
+    pub fn solved(&mut self, kind: &str, seconds: f32) -> bool {
+        let record = self.records.entry(kind.to_owned()).or_default();
+        let best = record.solved == 0 || seconds < record.best;
+        record.solved += 1;
+        if best {
+            record.best = seconds;
+        }
+        self.save();
+        best
+    }
+
+    fn save(&self) {
+        let text: String = self
+            .records
+            .iter()
+            .map(|(kind, record)| format!("{} {} {kind}\n", record.solved, record.best))
+            .collect();
+        if let Err(e) = storage::write(FILE, &text) {
+            eprintln!("failed to save statistics: {e}");
+        }
+    }
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour on
+pub fn format_time(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    let (h, m, s) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
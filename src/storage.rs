@@ -0,0 +1,42 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// directory where progress is remembered between runs
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("SUDORU_DATA") {
+        return dir.into();
+    }
+
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+    };
+
+    base.unwrap_or_default().join("sudoru")
+}
+
+/// read a file in the data directory, `None` if it doesn't exist yet
+pub fn read(name: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(data_dir().join(name)).ok()
+}
+
+/// write a file in the data directory, creating it if needed
+pub fn write(name: impl AsRef<Path>, contents: &str) -> io::Result<()> {
+    let path = data_dir().join(name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
+
+/// FNV-1a, used to give files stable names that don't change between builds
+pub fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
@@ -3,7 +3,6 @@ use macroquad::prelude::*;
 #[derive(Clone, Copy, Debug)]
 pub struct Theme {
     pub bg: Color,
-    pub padding: f32,
 
     pub border_color: Color,
     pub border_thick: f32,
@@ -17,6 +16,22 @@ pub struct Theme {
     pub cell_thick: f32,
 
     pub selected_bg: Color,
+
+    /// the main diagonals of X-Sudoku
+    pub diagonal_color: Color,
+    pub diagonal_thick: f32,
+
+    /// dashed outlines and sums of Killer cages
+    pub cage_color: Color,
+    pub cage_thick: f32,
+
+    /// marks of constraints, like windoku boxes and even / odd cells
+    pub mark_color: Color,
+    /// thermometers and arrows
+    pub line_color: Color,
+
+    /// colors players give cells, see-through so the selection shows
+    pub palette: [Color; 9],
 }
 
 impl Default for Theme {
@@ -39,7 +54,6 @@ const fn rgb(r: f32, g: f32, b: f32) -> Color {
 
 pub static LIGHT: Theme = Theme {
     bg: rgb(0.9, 0.9, 0.9),
-    padding: 0.05,
 
     border_color: rgb(0.2, 0.2, 0.2),
     border_thick: 4.0,
@@ -53,11 +67,31 @@ pub static LIGHT: Theme = Theme {
     cell_thick: 1.,
 
     selected_bg: rgba(0., 0., 0., 0.15),
+
+    diagonal_color: rgba(0.2, 0.4, 0.8, 0.35),
+    diagonal_thick: 3.,
+
+    cage_color: rgb(0.3, 0.3, 0.3),
+    cage_thick: 1.,
+
+    mark_color: rgba(0., 0., 0., 0.1),
+    line_color: rgb(0.75, 0.75, 0.75),
+
+    palette: [
+        rgba(0.6, 0.6, 0.6, 0.6),
+        rgba(0.95, 0.35, 0.35, 0.6),
+        rgba(1., 0.65, 0.25, 0.6),
+        rgba(1., 0.9, 0.3, 0.6),
+        rgba(0.45, 0.85, 0.4, 0.6),
+        rgba(0.3, 0.8, 0.8, 0.6),
+        rgba(0.35, 0.55, 0.95, 0.6),
+        rgba(0.7, 0.45, 0.9, 0.6),
+        rgba(1., 0.55, 0.8, 0.6),
+    ],
 };
 
 pub static DARK: Theme = Theme {
     bg: BLACK,
-    padding: 0.05,
 
     border_color: rgb(0.7, 0.2, 0.2),
     border_thick: 6.0,
@@ -71,4 +105,25 @@ pub static DARK: Theme = Theme {
     cell_thick: 1.,
 
     selected_bg: rgb(0.1, 0.2, 0.4),
+
+    diagonal_color: rgba(0.3, 0.5, 0.9, 0.4),
+    diagonal_thick: 3.,
+
+    cage_color: rgb(0.8, 0.8, 0.8),
+    cage_thick: 1.,
+
+    mark_color: rgba(1., 1., 1., 0.12),
+    line_color: rgb(0.3, 0.3, 0.3),
+
+    palette: [
+        rgba(0.4, 0.4, 0.4, 0.6),
+        rgba(0.6, 0.15, 0.15, 0.6),
+        rgba(0.65, 0.38, 0.1, 0.6),
+        rgba(0.6, 0.55, 0.1, 0.6),
+        rgba(0.2, 0.5, 0.2, 0.6),
+        rgba(0.1, 0.45, 0.45, 0.6),
+        rgba(0.15, 0.3, 0.65, 0.6),
+        rgba(0.4, 0.2, 0.55, 0.6),
+        rgba(0.65, 0.25, 0.5, 0.6),
+    ],
 };
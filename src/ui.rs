@@ -1,57 +1,91 @@
+use std::path::{Path, PathBuf};
+
 use crate::{
-    SAMPLE_COUNT,
-    board::{Board, Cell, PlaceError},
-    theme,
+    SAMPLE_COUNT, controls::Control, layout::Layout, mode::Mode, pad::Entry, storage, theme,
 };
-use macroquad::prelude::*;
+use macroquad::{miniquad, prelude::*};
+use sudoru_core::board::{Board, Cell, PlaceError, Position, from_symbol};
+use sudoru_core::{Difficulty, Seed, constraint::EdgeKind};
 
-use crate::board::Position;
 use crate::theme::Theme;
 
+/// the closest the board can be zoomed in
+const MAX_ZOOM: f32 = 4.;
+
 pub fn screen_size() -> (f32, f32) {
     (screen_width(), screen_height())
 }
 
-pub fn board_layout(screen_size: Vec2, padding: f32) -> Rect {
-    let w = screen_size.min_element() - padding * 2.;
-
-    Rect {
-        x: padding,
-        y: padding,
-        w,
-        h: w,
-    }
+/// Ctrl, or Cmd on macOS, is held down
+pub fn command_key_down() -> bool {
+    [
+        KeyCode::LeftControl,
+        KeyCode::RightControl,
+        KeyCode::LeftSuper,
+        KeyCode::RightSuper,
+    ]
+    .into_iter()
+    .any(is_key_down)
 }
 
 pub struct UI {
     pub themes: (&'static Theme, &'static Theme),
 
-    pub board_layout: Rect,
-    pub padding: f32,
+    pub layout: Layout,
     pub selected_cell: Option<usize>,
     pub screen_size: Vec2,
-    pub font_size: u16,
+    /// physical pixels to a logical one, see `screen_dpi_scale`
+    pub dpi: f32,
+    /// cells across the board on screen
+    pub side: usize,
 
     pub highlighted_cell: Option<usize>,
     pub board_texture: RenderTarget,
     pub board_texture_cam: Camera2D,
     pub redraw: bool,
+
+    /// how much larger than `layout.board` the board is drawn, 1 shows all of it
+    pub zoom: f32,
+    /// the top-left corner of the part of the board on screen, as a fraction of the board
+    pub view: Vec2,
+    /// the zoom `board_texture` was made for
+    texture_zoom: f32,
+
+    /// text shown above the board
+    pub header: String,
+
+    /// show the side panel with the controls and the number pad, which only
+    /// places numbers on a single board
+    pub panel: bool,
+    pub entry: Entry,
+    /// the board before every change, the last one first to undo
+    pub history: Vec<Board>,
 }
 
 impl UI {
     pub fn new() -> Self {
         Self {
             themes: (&theme::LIGHT, &theme::DARK),
-            board_layout: Rect::default(),
-            padding: 0.,
+            layout: Layout::default(),
             selected_cell: None,
             screen_size: Vec2::ZERO,
-            font_size: 0,
+            dpi: 1.,
+            side: 9,
 
             highlighted_cell: None,
             board_texture: render_target(1, 1),
             board_texture_cam: Camera2D::default(),
             redraw: false,
+
+            zoom: 1.,
+            view: Vec2::ZERO,
+            texture_zoom: 1.,
+
+            header: String::new(),
+
+            panel: false,
+            entry: Entry::Digit,
+            history: Vec::new(),
         }
     }
 
@@ -63,28 +97,59 @@ impl UI {
         self.themes.0
     }
 
+    pub fn is_dark(&self) -> bool {
+        std::ptr::eq(self.theme(), &theme::DARK)
+    }
+
+    /// show or hide the side panel, laid out again on the next `update`
+    pub fn set_panel(&mut self, panel: bool) {
+        self.panel = panel;
+        self.screen_size = Vec2::ZERO;
+    }
+
     pub fn update(&mut self) {
         self.redraw = false;
 
-        // toggle theme
-        if is_key_pressed(KeyCode::T) {
+        // toggle theme, unless T is a number on this board
+        let key = is_key_pressed(KeyCode::T) && self.insert_num().is_none();
+        if key || self.control_clicked() == Some(Control::Theme) {
             self.cycle_theme();
             self.redraw = true;
         }
 
+        self.pan_and_zoom();
+        self.update_layout();
+
+        // cell selection
+        if let Some(sel) = self.get_cell_clicked() {
+            if self.selected_cell == Some(sel) {
+                self.selected_cell = None;
+            } else {
+                self.selected_cell = Some(sel);
+            }
+            self.redraw = true;
+        }
+    }
+
+    /// Lay everything out again and make a new board texture when the window,
+    /// its DPI scale or the zoom changed
+    pub fn update_layout(&mut self) {
         let new_screen_size = screen_size().into();
-        if new_screen_size != self.screen_size {
+        let dpi = screen_dpi_scale();
+        if new_screen_size != self.screen_size || dpi != self.dpi || self.zoom != self.texture_zoom
+        {
             self.screen_size = new_screen_size;
+            self.dpi = dpi;
+            self.texture_zoom = self.zoom;
 
             self.redraw = true;
 
             // update drawing info
-            self.padding = self.theme().padding * self.screen_size.x;
-            self.board_layout = board_layout(self.screen_size, self.padding);
-            self.font_size = (0.05 * self.board_layout.w) as u16;
+            self.layout = Layout::new(self.screen_size, self.panel);
 
-            // update render target
-            let size = self.board_layout.size();
+            // update render target, in physical pixels and large enough to stay
+            // sharp when zoomed in
+            let size = self.layout.board.size() * self.zoom * dpi;
             let (target_w, target_h) = size.into();
 
             self.board_texture = render_target_ex(
@@ -96,38 +161,131 @@ impl UI {
                 },
             );
 
-            // update camera
-            let dpi = screen_dpi_scale();
+            // update camera, the texture spans -1 to 1 around its center
             self.board_texture_cam.render_target = Some(self.board_texture.clone());
 
             let tex_size = self.board_texture.texture.size();
-            let zoom = dpi / tex_size.min_element();
+            let zoom = 2. / tex_size.min_element();
 
             self.board_texture_cam.zoom = vec2(zoom, zoom);
             self.board_texture_cam.target = tex_size / 2.0;
 
             self.board_texture_cam.render_target = Some(self.board_texture.clone());
         }
+    }
+    /// The scroll wheel zooms in and out around the mouse, and the arrow keys move
+    /// the zoomed in board around
+    fn pan_and_zoom(&mut self) {
+        let (_, wheel) = mouse_wheel();
+        if wheel != 0. && self.layout.board.w > 0. {
+            // keep the point under the mouse where it is
+            let mouse =
+                (Vec2::from(mouse_position()) - self.layout.board.point()) / self.layout.board.w;
+            let under = self.view + mouse / self.zoom;
+            let factor = if wheel > 0. { 1.25 } else { 0.8 };
+            self.zoom = (self.zoom * factor).clamp(1., MAX_ZOOM);
+            self.view = under - mouse / self.zoom;
+        }
 
-        // cell selection
-        if let Some(sel) = self.get_cell_clicked() {
-            if self.selected_cell == Some(sel.index()) {
-                self.selected_cell = None;
-            } else {
-                self.selected_cell = Some(sel.index());
+        let step = 0.1 / self.zoom;
+        for (key, dir) in [
+            (KeyCode::Left, vec2(-step, 0.)),
+            (KeyCode::Right, vec2(step, 0.)),
+            (KeyCode::Up, vec2(0., -step)),
+            (KeyCode::Down, vec2(0., step)),
+        ] {
+            if is_key_pressed(key) {
+                self.view += dir;
             }
-            self.redraw = true;
         }
+        self.view = self
+            .view
+            .clamp(Vec2::ZERO, Vec2::splat(1. - 1. / self.zoom));
     }
+
+    /// the point of the screen that `point` of the board, a fraction of its size, is at
+    pub fn on_screen(&self, point: Vec2) -> Vec2 {
+        self.layout.board.point() + (point - self.view) * self.zoom * self.layout.board.w
+    }
+
     pub fn insert_num(&self) -> Option<(usize, u8)> {
-        if let Some(selected) = self.selected_cell {
-            if let Some(num) = self.num_key_clicked() {
-                return Some((selected, num));
-            }
+        if let Some(selected) = self.selected_cell
+            && let Some(num) = self.num_key_clicked()
+        {
+            return Some((selected, num));
         }
         None
     }
 
+    /// first file dropped onto the window this frame
+    pub fn dropped_file(&self) -> Option<PathBuf> {
+        get_dropped_files().into_iter().find_map(|file| file.path)
+    }
+
+    /// Ctrl+V (Cmd+V on macOS) saves the puzzle lines, f-puzzles JSON or SudokuPad
+    /// links on the clipboard to a file in the data directory, to open like a dropped file
+    pub fn pasted_file(&self) -> Option<PathBuf> {
+        if !(command_key_down() && is_key_pressed(KeyCode::V)) {
+            return None;
+        }
+        let text = miniquad::window::clipboard_get()?;
+        let hash = storage::hash(text.as_bytes());
+        let name = Path::new("pasted").join(format!("{hash:016x}.txt"));
+        if let Err(e) = storage::write(&name, &text) {
+            eprintln!("failed to save pasted puzzles: {e}");
+            return None;
+        }
+        Some(storage::data_dir().join(name))
+    }
+
+    /// N / P (or page down / page up) move to the next or previous puzzle,
+    /// D switches to the daily puzzle and R to a random one.
+    /// `true` when it's another puzzle now.
+    pub fn browse(&mut self, mode: &mut Mode, board: &mut Board) -> bool {
+        // letters are numbers on boards larger than 9×9
+        let typing = self.insert_num().is_some();
+        let clicked = self.control_clicked();
+        let pressed = |keys: &[KeyCode], control: Control| {
+            clicked == Some(control) || !typing && keys.iter().any(|&key| is_key_pressed(key))
+        };
+
+        let new_board = if pressed(&[KeyCode::N, KeyCode::PageDown], Control::Next) {
+            mode.next()
+        } else if pressed(&[KeyCode::P, KeyCode::PageUp], Control::Previous) {
+            mode.prev()
+        } else if pressed(&[KeyCode::D], Control::Daily) && !matches!(mode, Mode::Daily(_)) {
+            *mode = Mode::daily();
+            mode.board()
+        } else if pressed(&[KeyCode::R], Control::Random) {
+            // the comparisons follow the solution, so the next puzzle gets new ones
+            let mut empty = board.empty();
+            let comparison = EdgeKind::Less.name();
+            empty.constraints.retain(|c| c.name() != comparison);
+            *mode = Mode::Random {
+                comparison: empty.constraints.len() < board.constraints.len(),
+                empty,
+                killer: !board.cages.is_empty(),
+                jigsaw: board.regions.is_some(),
+                difficulty: Difficulty::Medium,
+                seed: Seed::random(),
+            };
+            mode.board()
+        } else {
+            None
+        };
+
+        self.header = mode.header();
+        let Some(new_board) = new_board else {
+            return false;
+        };
+        *board = new_board;
+        self.history.clear();
+        self.selected_cell = None;
+        self.highlighted_cell = None;
+        self.redraw = true;
+        true
+    }
+
     pub fn highlight(&mut self, board: &Board, index: impl Position, value: Cell, err: PlaceError) {
         // find offending cell
         let victim = value;
@@ -139,23 +297,37 @@ impl UI {
                 .map(|c| c.0)
                 .unwrap()
         };
-        let (x, y) = index.coords();
+        let (x, y) = board.coords(index);
 
         let offender = match err {
-            PlaceError::AlreadyInCell => index.index(),
-            PlaceError::AlreadyInRow => (find(board.row(index)), y).index(),
-            PlaceError::AlreadyInCol => (x, find(&board.col(index))).index(),
+            PlaceError::AlreadyInCell | PlaceError::Given | PlaceError::CageSum => {
+                board.index(index)
+            }
+            PlaceError::AlreadyInRow => board.index((find(board.row(index)), y)),
+            PlaceError::AlreadyInCol => board.index((x, find(&board.col(index)))),
             PlaceError::AlreadyInSeg => {
-                let (seg_x, seg_y) = index.segment().coords();
-                let seg_index = find(&board.segment(index));
-                (seg_x + seg_index % 3, seg_y + seg_index / 3).index()
+                let seg_cells = board.seg_cells(board.seg_index(index));
+                seg_cells[find(&board.segment(index))]
             }
+            PlaceError::AlreadyInCage => {
+                let cage = board.cage(index).unwrap();
+                let cell = cage.cells.iter().find(|&&i| board.cells[i] == victim);
+                *cell.unwrap()
+            }
+            PlaceError::Constraint => board
+                .constraint_conflict(board.index(index), victim.unwrap())
+                .unwrap(),
+            PlaceError::AlreadyInDiagonal => board
+                .diagonal_cells(index)
+                .into_iter()
+                .find(|&i| board.cells[i] == victim)
+                .unwrap(),
         };
 
         self.highlighted_cell = Some(offender);
     }
 
-    pub fn get_cell_clicked(&self) -> Option<impl Position> {
+    pub fn get_cell_clicked(&self) -> Option<usize> {
         let mouse_clicked = is_mouse_button_pressed(MouseButton::Left);
 
         if !mouse_clicked {
@@ -163,19 +335,24 @@ impl UI {
         }
 
         let mouse_pos: Vec2 = mouse_position().into();
-        if !self.board_layout.contains(mouse_pos) {
+        if !self.layout.board.contains(mouse_pos) {
             return None;
         }
 
-        let cell_size = self.board_layout.w / 9.;
-        let pos = mouse_pos - self.board_layout.point();
+        // the fraction of the board that was clicked
+        let pos =
+            self.view + (mouse_pos - self.layout.board.point()) / self.layout.board.w / self.zoom;
 
-        let index = (pos / cell_size).floor();
-        let index = index.x as usize + index.y as usize * 9;
+        let (x, y) = (pos * self.side as f32).floor().into();
+        let (x, y) = (
+            (x as usize).min(self.side - 1),
+            (y as usize).min(self.side - 1),
+        );
 
-        Some(index)
+        Some((x, y).index(self.side))
     }
 
+    /// a number key, or a letter for numbers above 9 when the board has them
     pub fn num_key_clicked(&self) -> Option<u8> {
         let key = get_last_key_pressed()?;
 
@@ -189,9 +366,43 @@ impl UI {
             KeyCode::Key7 => 7,
             KeyCode::Key8 => 8,
             KeyCode::Key9 => 9,
-            _ => return None,
+            key => from_symbol(letter(key)?)?,
         };
 
-        Some(num)
+        (num as usize <= self.side).then_some(num)
     }
 }
+
+/// the letter on a letter key
+fn letter(key: KeyCode) -> Option<char> {
+    const LETTERS: [KeyCode; 26] = [
+        KeyCode::A,
+        KeyCode::B,
+        KeyCode::C,
+        KeyCode::D,
+        KeyCode::E,
+        KeyCode::F,
+        KeyCode::G,
+        KeyCode::H,
+        KeyCode::I,
+        KeyCode::J,
+        KeyCode::K,
+        KeyCode::L,
+        KeyCode::M,
+        KeyCode::N,
+        KeyCode::O,
+        KeyCode::P,
+        KeyCode::Q,
+        KeyCode::R,
+        KeyCode::S,
+        KeyCode::T,
+        KeyCode::U,
+        KeyCode::V,
+        KeyCode::W,
+        KeyCode::X,
+        KeyCode::Y,
+        KeyCode::Z,
+    ];
+    let i = LETTERS.iter().position(|&k| k == key)?;
+    Some((b'A' + i as u8) as char)
+}
@@ -0,0 +1,104 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use crate::{
+    board::Board,
+    generate::{Strategy, generate_from},
+    seed::Seed,
+};
+
+/// how many puzzles in a row may be equivalent to earlier ones before the rules are
+/// taken to have no more distinct puzzles
+const MAX_DUPLICATES: usize = 100;
+
+/// number of threads to generate on, one per core
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Generate `count` distinct puzzles with the shape and rules of `empty` on `threads` threads.
+///
+/// Puzzle `n` comes from seed `first + n`, and puzzles are passed to `found`
+/// in seed order as soon as they're ready, so the output only depends on the
+/// seed and not on the number of threads.
+/// Puzzles equivalent to an earlier one (see `Board::canonical`) are skipped,
+/// and after `MAX_DUPLICATES` of them in a row it stops with the ones it has,
+/// since small boards only have so many distinct puzzles.
+/// Stops early when `found` returns false.
+/// Returns how many puzzles were passed to `found`, `None` if no board follows the rules.
+pub fn generate_batch(
+    empty: &Board,
+    strategy: Strategy,
+    count: usize,
+    first: Seed,
+    threads: usize,
+    mut found: impl FnMut(Seed, Board) -> bool,
+) -> Option<usize> {
+    if count == 0 {
+        return Some(0);
+    }
+
+    let next = Arc::new(AtomicU64::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            let (next, stop, sender) = (next.clone(), stop.clone(), sender.clone());
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let n = next.fetch_add(1, Ordering::Relaxed);
+                    let board = generate_from(empty, strategy, Seed(first.0.wrapping_add(n)));
+                    if sender.send((n, board)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut seen = HashSet::new();
+        let mut emit = 0;
+        let mut emitted = 0;
+        let mut duplicates = 0;
+        let mut solvable = true;
+
+        'receive: for (n, board) in &receiver {
+            pending.insert(n, board);
+
+            while let Some(board) = pending.remove(&emit) {
+                // whether the rules can be followed doesn't depend on the seed
+                let Some(board) = board else {
+                    solvable = false;
+                    break 'receive;
+                };
+                let seed = Seed(first.0.wrapping_add(emit));
+                emit += 1;
+
+                if !seen.insert(board.canonical()) {
+                    duplicates += 1;
+                    if duplicates == MAX_DUPLICATES {
+                        break 'receive;
+                    }
+                    continue;
+                }
+                duplicates = 0;
+                emitted += 1;
+                if !found(seed, board) || emitted == count {
+                    break 'receive;
+                }
+            }
+        }
+        stop.store(true, Ordering::Relaxed);
+        drop(receiver);
+        solvable.then_some(emitted)
+    })
+}
@@ -0,0 +1,192 @@
+use std::str::FromStr;
+
+use rand::{
+    Rng,
+    seq::{IndexedRandom, SliceRandom},
+};
+
+use super::*;
+use crate::solve::Candidates;
+
+/// Killer Sudoku: cells that add up to `sum` without repeating a number
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cage {
+    /// indices of the cells, lowest first
+    pub cells: Vec<usize>,
+    pub sum: u32,
+}
+
+impl Cage {
+    pub fn new(mut cells: Vec<usize>, sum: u32) -> Self {
+        cells.sort();
+        Self { cells, sum }
+    }
+
+    /// where the sum is written, the top-left cell
+    pub fn corner(&self) -> usize {
+        self.cells[0]
+    }
+
+    /// Numbers that can go in one more empty cell, so that `empty` other cells can
+    /// still make up the sum. `used` and `filled` are the numbers already in the cage
+    /// and their sum, on a board with numbers up to `side`.
+    pub fn fitting(&self, used: Candidates, filled: u32, empty: usize, side: usize) -> Candidates {
+        let mut fitting = 0;
+        for num in 1..=side as u32 {
+            let bit = 1 << (num - 1);
+            if used & bit != 0 {
+                continue;
+            }
+            let left: Vec<u32> = (1..=side as u32)
+                .filter(|&n| n != num && used & (1 << (n - 1)) == 0)
+                .collect();
+            if left.len() < empty {
+                continue;
+            }
+            let min: u32 = left[..empty].iter().sum();
+            let max: u32 = left[left.len() - empty..].iter().sum();
+
+            let total = filled + num;
+            let fits = match empty {
+                // the last cell has to make up exactly what's left
+                1 => self
+                    .sum
+                    .checked_sub(total)
+                    .is_some_and(|rest| left.contains(&rest)),
+                _ => total + min <= self.sum && self.sum <= total + max,
+            };
+            if fits {
+                fitting |= bit;
+            }
+        }
+        fitting
+    }
+
+    /// `fitting` for cell `index` of the cage, from the other cells on `board`
+    pub fn fitting_in(&self, board: &Board, index: usize) -> Candidates {
+        let (mut used, mut filled, mut empty) = (0, 0, 0);
+        for &i in self.cells.iter().filter(|&&i| i != index) {
+            match board.cells[i] {
+                Some(num) => {
+                    used |= 1 << (num - 1);
+                    filled += num as u32;
+                }
+                None => empty += 1,
+            }
+        }
+        self.fitting(used, filled, empty, board.width())
+    }
+}
+
+/// `cells` with their candidates as `options`: for every cell the numbers it has
+/// in at least one way to fill the cage with different numbers adding up to `sum`
+pub fn cage_options(options: &[Candidates], sum: u32) -> Vec<Candidates> {
+    fn fill(
+        options: &[Candidates],
+        sum: u32,
+        chosen: &mut Vec<u32>,
+        used: Candidates,
+        found: &mut [Candidates],
+    ) {
+        let total: u32 = chosen.iter().sum();
+        let Some(&cell) = options.get(chosen.len()) else {
+            if total == sum {
+                for (found, num) in found.iter_mut().zip(chosen.iter()) {
+                    *found |= 1 << (num - 1);
+                }
+            }
+            return;
+        };
+
+        for num in 1..=Candidates::BITS {
+            let bit = 1 << (num - 1);
+            if cell & bit == 0 || used & bit != 0 || total + num > sum {
+                continue;
+            }
+            chosen.push(num);
+            fill(options, sum, chosen, used | bit, found);
+            chosen.pop();
+        }
+    }
+
+    let mut found = vec![0; options.len()];
+    fill(options, sum, &mut Vec::new(), 0, &mut found);
+    found
+}
+
+impl Display for Cage {
+    /// `sum:cell,cell,...`, like `10:0,1,9`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cells: Vec<String> = self.cells.iter().map(usize::to_string).collect();
+        write!(f, "{}:{}", self.sum, cells.join(","))
+    }
+}
+
+impl FromStr for Cage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid cage {s:?}");
+        let (sum, cells) = s.split_once(':').ok_or_else(invalid)?;
+
+        let cells = cells
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| invalid())?;
+        Ok(Cage::new(cells, sum.parse().map_err(|_| invalid())?))
+    }
+}
+
+impl Board {
+    /// the cage `pos` is in, if any
+    pub fn cage(&self, pos: impl Position) -> Option<&Cage> {
+        let index = self.index(pos);
+        self.cages.iter().find(|cage| cage.cells.contains(&index))
+    }
+
+    /// Random cages of 1 to `max` cells covering the whole board,
+    /// with sums from the numbers in this filled board
+    pub fn random_cages(&self, max: usize, rng: &mut impl Rng) -> Vec<Cage> {
+        let mut order: Vec<usize> = (0..self.size()).collect();
+        order.shuffle(rng);
+
+        let mut caged = vec![false; self.size()];
+        let mut cages = Vec::new();
+
+        for start in order {
+            if caged[start] {
+                continue;
+            }
+            let size = rng.random_range(1..=max);
+            let mut cells = vec![start];
+            caged[start] = true;
+
+            while cells.len() < size {
+                let used: Vec<Cell> = cells.iter().map(|&i| self.cells[i]).collect();
+                let mut next: Vec<usize> = cells
+                    .iter()
+                    .flat_map(|&i| self.neighbours(i))
+                    .filter(|&i| !caged[i] && !used.contains(&self.cells[i]))
+                    .collect();
+                next.sort();
+                next.dedup();
+
+                let Some(&cell) = next.choose(rng) else {
+                    break;
+                };
+                caged[cell] = true;
+                cells.push(cell);
+            }
+
+            let sum = cells
+                .iter()
+                .filter_map(|&i| self.cells[i])
+                .map(u32::from)
+                .sum();
+            cages.push(Cage::new(cells, sum));
+        }
+        cages.sort_by_key(Cage::corner);
+        cages
+    }
+}
@@ -0,0 +1,198 @@
+use super::*;
+use crate::format;
+
+type Grid = Vec<Vec<u8>>;
+
+/// every order of `n` things
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut result = Vec::new();
+    for perm in permutations(n - 1) {
+        for i in 0..n {
+            let mut perm = perm.clone();
+            perm.insert(i, n - 1);
+            result.push(perm);
+        }
+    }
+    result.sort();
+    result
+}
+
+/// every column order that keeps the stacks of `shape` together
+fn col_orders(shape: Shape) -> Vec<Vec<usize>> {
+    let width = shape.seg_width;
+    let stacks = shape.side() / width;
+    let within = permutations(width);
+
+    let mut orders = Vec::new();
+    for stack_order in permutations(stacks) {
+        // one order inside every stack, counting like an odometer
+        let mut choice = vec![0; stacks];
+        loop {
+            orders.push(
+                (0..shape.side())
+                    .map(|j| {
+                        let stack = j / width;
+                        stack_order[stack] * width + within[choice[stack]][j % width]
+                    })
+                    .collect(),
+            );
+
+            let Some(digit) = choice.iter().rposition(|&c| c + 1 < within.len()) else {
+                break;
+            };
+            choice[digit] += 1;
+            choice[digit + 1..].fill(0);
+        }
+    }
+    orders
+}
+
+/// Depth first search over row orders for one column order,
+/// skipping any branch that can't beat the best grid found so far.
+struct Search<'a> {
+    grid: &'a Grid,
+    /// rows in a band
+    height: usize,
+    cols: Vec<usize>,
+    result: Grid,
+    best: &'a mut Option<Grid>,
+}
+
+/// choices made for the rows placed so far
+#[derive(Clone, Copy)]
+struct State {
+    /// bitmask of bands
+    used_bands: u32,
+    band: usize,
+    /// bitmask of rows inside `band`
+    used_rows: u32,
+    /// new name of every number, 0 while it hasn't appeared yet
+    labels: [u8; 26],
+    next_label: u8,
+}
+
+impl Search<'_> {
+    fn rows(&mut self, i: usize, state: State) {
+        if i == self.grid.len() {
+            *self.best = Some(self.result.clone());
+            return;
+        }
+
+        let offset = i % self.height;
+        for band in 0..self.grid.len() / self.height {
+            if offset == 0 && state.used_bands & 1 << band != 0 || offset != 0 && band != state.band
+            {
+                continue;
+            }
+
+            for row in 0..self.height {
+                if offset != 0 && state.used_rows & 1 << row != 0 {
+                    continue;
+                }
+                let mut state = state;
+                if offset == 0 {
+                    state.used_bands |= 1 << band;
+                    state.band = band;
+                    state.used_rows = 0;
+                }
+                state.used_rows |= 1 << row;
+
+                let source = &self.grid[band * self.height + row];
+                for (cell, &col) in self.result[i].iter_mut().zip(&self.cols) {
+                    *cell = match source[col] {
+                        0 => 0,
+                        num => {
+                            let label = &mut state.labels[num as usize];
+                            if *label == 0 {
+                                *label = state.next_label;
+                                state.next_label += 1;
+                            }
+                            *label
+                        }
+                    };
+                }
+
+                // the best grid can change deeper in the search,
+                // so the whole prefix is compared every time
+                if let Some(best) = self.best
+                    && self.result[..=i] > best[..=i]
+                {
+                    continue;
+                }
+
+                self.rows(i + 1, state);
+            }
+        }
+    }
+}
+
+impl Board {
+    /// The same string for every board that can be turned into this one by
+    /// relabelling numbers, moving rows / columns within their band / stack,
+    /// moving whole bands / stacks and transposing (when segments are square).
+    /// It's the smallest `line` of all those boards, with `.` before digits.
+    ///
+    /// The search tries every column order, which is too many above 9×9,
+    /// so larger boards are only equivalent to themselves and just give their `line`.
+    /// So are variants, since moving rows breaks the diagonals and regions and
+    /// relabelling the sums. Their regions and cages follow the line.
+    pub fn canonical(&self) -> String {
+        if self.width() > 9 || !self.is_classic() {
+            return self.line() + &format::rules_comment(self);
+        }
+
+        let side = self.width();
+        let grid: Grid = self
+            .cells
+            .chunks_exact(side)
+            .map(|row| row.iter().map(|cell| cell.unwrap_or(0)).collect())
+            .collect();
+        let transposed: Grid = (0..side)
+            .map(|y| (0..side).map(|x| grid[x][y]).collect())
+            .collect();
+
+        let mut grids = vec![&grid];
+        if self.shape.transposed() == self.shape {
+            grids.push(&transposed);
+        }
+
+        let start = State {
+            used_bands: 0,
+            band: 0,
+            used_rows: 0,
+            labels: [0; _],
+            next_label: 1,
+        };
+        let mut best = None;
+
+        for grid in grids {
+            for cols in col_orders(self.shape) {
+                let mut search = Search {
+                    grid,
+                    height: self.shape.seg_height,
+                    cols,
+                    result: vec![vec![0; side]; side],
+                    best: &mut best,
+                };
+                search.rows(0, start);
+            }
+        }
+
+        best.expect("at least one arrangement")
+            .iter()
+            .flatten()
+            .map(|&num| match num {
+                0 => '.',
+                num => symbol(num),
+            })
+            .collect()
+    }
+
+    /// the boards are the same up to the changes described in `canonical`
+    pub fn is_equivalent(&self, other: &Board) -> bool {
+        self.shape == other.shape && self.canonical() == other.canonical()
+    }
+}
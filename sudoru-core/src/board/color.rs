@@ -0,0 +1,68 @@
+use super::*;
+
+/// how many colors cells can be given, numbered from 1
+pub const COLORS: u8 = 9;
+
+/// Colors a player gives cells while solving, to keep track of chains or parity.
+/// `Board::colors` has a bit for every color of a cell, bit `n - 1` for color `n`.
+impl Board {
+    /// give cell `index` color `color`, or take it away if the cell already has it
+    pub fn toggle_color(&mut self, index: usize, color: u8) {
+        self.colors[index] ^= 1 << (color - 1);
+    }
+
+    /// the colors of cell `index`, lowest first
+    pub fn cell_colors(&self, index: usize) -> Vec<u8> {
+        (1..=COLORS)
+            .filter(|color| self.colors[index] & 1 << (color - 1) != 0)
+            .collect()
+    }
+
+    /// `cell:colors` for every colored cell, like `12:13 40:5` when cell 12 has
+    /// colors 1 and 3 and cell 40 color 5. `None` without any colors.
+    pub fn colors_line(&self) -> Option<String> {
+        cells_line(self.size(), |i| self.cell_colors(i))
+    }
+
+    /// read colors written by `colors_line`, skipping what doesn't fit the board
+    pub fn read_colors<'a>(&mut self, words: impl IntoIterator<Item = &'a str>) {
+        read_cells_line(words, self.size(), COLORS, |cell, color| {
+            self.colors[cell] |= 1 << (color - 1);
+        });
+    }
+}
+
+/// `cell:numbers` for every cell with any of `numbers`
+pub(super) fn cells_line(size: usize, numbers: impl Fn(usize) -> Vec<u8>) -> Option<String> {
+    let cells: Vec<String> = (0..size)
+        .filter_map(|i| {
+            let numbers = numbers(i);
+            let symbols: String = numbers.iter().map(|&n| symbol(n)).collect();
+            (!numbers.is_empty()).then(|| format!("{i}:{symbols}"))
+        })
+        .collect();
+    (!cells.is_empty()).then(|| cells.join(" "))
+}
+
+/// every cell and number of `words` written by `cells_line`, for cells below
+/// `size` and numbers up to `max`
+pub(super) fn read_cells_line<'a>(
+    words: impl IntoIterator<Item = &'a str>,
+    size: usize,
+    max: u8,
+    mut add: impl FnMut(usize, u8),
+) {
+    for word in words {
+        let Some((cell, numbers)) = word.split_once(':') else {
+            continue;
+        };
+        let Some(cell) = cell.parse().ok().filter(|&i: &usize| i < size) else {
+            continue;
+        };
+        for num in numbers.chars().filter_map(from_symbol) {
+            if num <= max {
+                add(cell, num);
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use super::*;
+
+pub trait Position: Copy {
+    /// index into the cells of a board `width` cells wide
+    fn index(self, width: usize) -> usize;
+
+    /// (x, y) on a board `width` cells wide
+    fn coords(self, width: usize) -> (usize, usize);
+}
+
+impl Position for usize {
+    fn index(self, _width: usize) -> usize {
+        self
+    }
+    fn coords(self, width: usize) -> (usize, usize) {
+        (self % width, self / width)
+    }
+}
+impl Position for (usize, usize) {
+    fn index(self, width: usize) -> usize {
+        self.0 + self.1 * width
+    }
+    fn coords(self, _width: usize) -> (usize, usize) {
+        self
+    }
+}
+impl Board {
+    pub fn row(&self, index: impl Position) -> &[Cell] {
+        let (_, y) = self.coords(index);
+        self.cells.chunks_exact(self.width()).nth(y).unwrap()
+    }
+    pub fn col(&self, index: impl Position) -> Vec<Cell> {
+        let (x, _) = self.coords(index);
+        self.cells
+            .iter()
+            .skip(x)
+            .step_by(self.width())
+            .copied()
+            .collect()
+    }
+
+    /// the cells of the segment `pos` is in, left to right, top to bottom
+    pub fn segment(&self, pos: impl Position) -> Vec<Cell> {
+        let seg = self.seg_index(pos);
+        self.seg_cells(seg)
+            .into_iter()
+            .map(|i| self.cells[i])
+            .collect()
+    }
+
+    /// the segment `pos` is in, its jigsaw region if there are `regions`
+    pub fn seg_index(&self, pos: impl Position) -> usize {
+        match &self.regions {
+            Some(regions) => regions[self.index(pos)],
+            None => self.shape.seg_index(pos),
+        }
+    }
+
+    /// indices of the cells in segment `seg`, left to right, top to bottom
+    pub fn seg_cells(&self, seg: usize) -> Vec<usize> {
+        match &self.regions {
+            Some(regions) => (0..self.size()).filter(|&i| regions[i] == seg).collect(),
+            None => self.shape.seg_cells(seg).collect(),
+        }
+    }
+
+    /// the cells left, right, above and below cell `index` that are on the board
+    pub fn neighbours(&self, index: usize) -> impl Iterator<Item = usize> + use<> {
+        let side = self.width();
+        let (x, y) = self.coords(index);
+        [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ]
+        .into_iter()
+        .filter(move |&(x, y)| x < side && y < side)
+        .map(move |pos| pos.index(side))
+    }
+
+    /// cells on the main diagonals through `pos`, none if `diagonals` is off
+    pub fn diagonal_cells(&self, pos: impl Position) -> Vec<usize> {
+        let side = self.width();
+        let (x, y) = self.coords(pos);
+        if !self.diagonals {
+            return Vec::new();
+        }
+
+        let mut cells = Vec::new();
+        if x == y {
+            cells.extend((0..side).map(|i| (i, i).index(side)));
+        }
+        if x + y == side - 1 {
+            cells.extend((0..side).map(|i| (side - 1 - i, i).index(side)));
+        }
+        cells
+    }
+
+    /// Every group of cells that holds every number once:
+    /// rows, columns, segments, the diagonals when they count
+    /// and those of `constraints`
+    pub fn units(&self) -> Vec<Vec<usize>> {
+        let mut units = self.shape.units();
+        if self.regions.is_some() {
+            // every third unit is a segment, see `Shape::units`
+            for seg in 0..self.width() {
+                units[seg * 3 + 2] = self.seg_cells(seg);
+            }
+        }
+        if self.diagonals {
+            let side = self.width();
+            units.push((0..side).map(|i| (i, i).index(side)).collect());
+            units.push((0..side).map(|i| (side - 1 - i, i).index(side)).collect());
+        }
+        for constraint in &self.constraints {
+            units.extend(constraint.units(self));
+        }
+        units
+    }
+
+    pub fn get(&self, pos: impl Position) -> Option<&Cell> {
+        self.cells.get(self.index(pos))
+    }
+}
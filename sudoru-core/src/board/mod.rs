@@ -0,0 +1,214 @@
+mod cage;
+mod canonical;
+mod color;
+mod index;
+mod parse;
+mod pencil;
+mod placement;
+mod region;
+mod shape;
+mod transform;
+
+pub use cage::{Cage, cage_options};
+pub use color::COLORS;
+pub use index::Position;
+pub use parse::ParseError;
+pub use pencil::Pencil;
+pub use placement::PlaceError;
+pub use shape::Shape;
+pub use transform::TransformError;
+
+use std::{fmt::Display, sync::Arc};
+
+use crate::constraint::Constraint;
+
+pub type Cell = Option<u8>;
+
+/// indexed by a number of 1..=25 - 1, digits first and letters after 9
+/// example: CELL_STR[2 - 1] == "2", CELL_STR[10 - 1] == "A"
+pub static CELL_STR: [&str; 25] = [
+    "1", "2", "3", "4", "5", "6", "7", "8", "9", "A", "B", "C", "D", "E", "F", "G", "H", "I", "J",
+    "K", "L", "M", "N", "O", "P",
+];
+
+/// the character for a number, the same as in `CELL_STR`
+pub fn symbol(num: u8) -> char {
+    CELL_STR[num as usize - 1].chars().next().unwrap()
+}
+
+/// the number a character stands for, upper or lower case
+pub fn from_symbol(c: char) -> Option<u8> {
+    let c = c.to_ascii_uppercase();
+    let pos = CELL_STR.iter().position(|s| s.starts_with(c))?;
+    Some(pos as u8 + 1)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Board {
+    pub shape: Shape,
+    pub cells: Vec<Cell>,
+    /// cells that are part of the puzzle and can't be changed
+    pub givens: Vec<bool>,
+    /// X-Sudoku: both main diagonals hold every number once as well
+    pub diagonals: bool,
+    /// Killer Sudoku, a cell is in one cage at most
+    pub cages: Vec<Cage>,
+    /// Jigsaw Sudoku: the segment of every cell, when segments aren't
+    /// the shape's rectangles but any connected regions of `width` cells
+    pub regions: Option<Vec<usize>>,
+    /// extra rules, all of which have to hold
+    pub constraints: Vec<Arc<dyn Constraint>>,
+    /// colors the player gave every cell, not part of the puzzle
+    pub colors: Vec<u16>,
+    /// pencil marks the player wrote in the corners and centers of cells, see `Pencil`
+    pub corner_marks: Vec<u32>,
+    pub center_marks: Vec<u32>,
+}
+impl Board {
+    /// an empty 9×9 board
+    pub fn new() -> Self {
+        Self::with_shape(Shape::STANDARD)
+    }
+    pub fn with_shape(shape: Shape) -> Self {
+        Self {
+            shape,
+            cells: vec![None; shape.size()],
+            givens: vec![false; shape.size()],
+            diagonals: false,
+            cages: Vec::new(),
+            regions: None,
+            constraints: Vec::new(),
+            colors: vec![0; shape.size()],
+            corner_marks: vec![0; shape.size()],
+            center_marks: vec![0; shape.size()],
+        }
+    }
+
+    /// no variant rules, cages, regions or constraints
+    pub fn is_classic(&self) -> bool {
+        !self.diagonals
+            && self.cages.is_empty()
+            && self.regions.is_none()
+            && self.constraints.is_empty()
+    }
+
+    /// an empty board with the same shape, rules, regions and constraints, but without cages
+    pub fn empty(&self) -> Self {
+        Self {
+            diagonals: self.diagonals,
+            regions: self.regions.clone(),
+            constraints: self.constraints.clone(),
+            ..Self::with_shape(self.shape)
+        }
+    }
+    pub const fn width(&self) -> usize {
+        self.shape.side()
+    }
+    pub const fn height(&self) -> usize {
+        self.shape.side()
+    }
+    pub const fn size(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    /// index into `cells`
+    pub fn index(&self, pos: impl Position) -> usize {
+        pos.index(self.width())
+    }
+    /// (x, y)
+    pub fn coords(&self, pos: impl Position) -> (usize, usize) {
+        pos.coords(self.width())
+    }
+
+    /// mark every filled cell as a given
+    pub fn lock_givens(&mut self) {
+        for (given, cell) in self.givens.iter_mut().zip(&self.cells) {
+            *given = cell.is_some();
+        }
+    }
+
+    /// these givens, with the other cells filled in, colored and marked from `progress`
+    pub fn restore(&self, progress: &Board) -> Board {
+        let mut board = self.clone();
+        if progress.shape != self.shape {
+            return board;
+        }
+        board.colors.clone_from(&progress.colors);
+        board.corner_marks.clone_from(&progress.corner_marks);
+        board.center_marks.clone_from(&progress.center_marks);
+        for (i, cell) in progress.cells.iter().enumerate() {
+            if !board.givens[i] {
+                board.cells[i] = *cell;
+            }
+        }
+        board
+    }
+
+    /// Like `restore`, with the progress written as a `line`. Its numbers are checked
+    /// against these rules, so the progress of variants is read too.
+    /// `None` if it isn't a line of numbers that follow the rules.
+    pub fn restore_line(&self, line: &str) -> Option<Board> {
+        let progress = Board::from_line(line, |board| {
+            // other shapes aren't restored anyway
+            if board.shape == self.shape {
+                *board = Board {
+                    cages: self.cages.clone(),
+                    ..self.empty()
+                };
+            }
+            Ok(())
+        });
+        Some(self.restore(&progress.ok()?))
+    }
+
+    /// number of filled cells
+    pub fn clue_count(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.is_some()).count()
+    }
+
+    /// every cell is filled.
+    /// Since `place` refuses conflicting numbers, a full board is solved.
+    pub fn is_solved(&self) -> bool {
+        self.cells.iter().all(Option::is_some)
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Shape {
+            seg_width,
+            seg_height,
+        } = self.shape;
+
+        // the segments of a row are 2 characters per cell, "+" lines up with "|"
+        let stacks = self.width() / seg_width;
+        let separator = (0..stacks)
+            .map(|i| "-".repeat(seg_width * 2 + usize::from(i != 0 && i != stacks - 1)))
+            .collect::<Vec<_>>()
+            .join("+");
+
+        for (i, row) in self.cells.chunks_exact(self.width()).enumerate() {
+            if i % seg_height == 0 && i != 0 {
+                writeln!(f, "{separator}")?;
+            }
+
+            for (j, cell) in row.iter().enumerate() {
+                if j % seg_width == 0 && j != 0 {
+                    write!(f, "| ")?;
+                }
+                match cell {
+                    Some(val) => write!(f, "{} ", symbol(*val))?,
+                    None => write!(f, ". ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
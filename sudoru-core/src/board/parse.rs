@@ -0,0 +1,97 @@
+use std::str::FromStr;
+
+use super::*;
+
+/// Failed to read a board from a single line of text
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParseError {
+    /// the line doesn't have as many cells as any board
+    Length(usize),
+    /// a character that isn't a number of the board or an empty cell
+    InvalidChar(char),
+    /// the givens break a rule
+    Conflict(usize, PlaceError),
+    /// a cage that isn't `sum:cell,cell,...` with cells on the board
+    InvalidCage,
+    /// jigsaw regions that aren't connected regions of one segment's size
+    InvalidRegions,
+    /// a constraint `constraint::parse` doesn't know
+    InvalidConstraint,
+    /// a word after a rule that doesn't take any
+    InvalidRule,
+    /// f-puzzles data that isn't a puzzle, saying what's wrong with it
+    InvalidFPuzzles(&'static str),
+    /// an f-puzzles rule sudoru doesn't play
+    Unsupported(&'static str),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Length(len) => write!(f, "no board has {len} cells"),
+            Self::InvalidChar(c) => write!(f, "invalid character {c:?}"),
+            Self::Conflict(index, e) => write!(f, "cell {index}: {e:?}"),
+            Self::InvalidCage => write!(f, "invalid cage"),
+            Self::InvalidRegions => write!(f, "invalid regions"),
+            Self::InvalidConstraint => write!(f, "invalid constraint"),
+            Self::InvalidRule => write!(f, "invalid rule"),
+            Self::InvalidFPuzzles(what) => write!(f, "invalid f-puzzles data: {what}"),
+            Self::Unsupported(rule) => write!(f, "unsupported rule: {rule}"),
+        }
+    }
+}
+
+impl FromStr for Board {
+    type Err = ParseError;
+
+    /// One line of cells, `.` or `0` for empty cells and `CELL_STR` for numbers.
+    /// The number of cells decides the shape, 81 for a 9×9 board.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Board::from_line(s, |_| Ok(()))
+    }
+}
+
+impl Board {
+    /// Like `from_str`, with `rules` adding cages or regions to the empty board
+    /// so the givens are checked against them
+    pub fn from_line(
+        s: &str,
+        rules: impl FnOnce(&mut Board) -> Result<(), ParseError>,
+    ) -> Result<Board, ParseError> {
+        let s = s.trim();
+
+        let len = s.chars().count();
+        let shape = Shape::from_size(len).ok_or(ParseError::Length(len))?;
+        let mut board = Board::with_shape(shape);
+        rules(&mut board)?;
+
+        for (index, c) in s.chars().enumerate() {
+            let cell = match c {
+                '.' | '0' => None,
+                c => match from_symbol(c) {
+                    Some(num) if num as usize <= shape.side() => Some(num),
+                    _ => return Err(ParseError::InvalidChar(c)),
+                },
+            };
+            if cell.is_some() {
+                board
+                    .place(index, cell)
+                    .map_err(|e| ParseError::Conflict(index, e))?;
+            }
+        }
+        board.lock_givens();
+
+        Ok(board)
+    }
+
+    /// the board on a single line, the inverse of `Board::from_str`
+    pub fn line(&self) -> String {
+        self.cells
+            .iter()
+            .map(|cell| match cell {
+                Some(num) => symbol(*num),
+                None => '.',
+            })
+            .collect()
+    }
+}
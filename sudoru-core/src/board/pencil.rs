@@ -0,0 +1,71 @@
+use super::{
+    color::{cells_line, read_cells_line},
+    *,
+};
+
+/// Where a pencil mark is written in its cell. Corner marks note where a number
+/// can go in a segment, center marks the numbers a cell could still hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pencil {
+    Corner,
+    Center,
+}
+
+impl Pencil {
+    /// the word in front of these marks in saved progress
+    pub fn name(self) -> &'static str {
+        match self {
+            Pencil::Corner => "corner",
+            Pencil::Center => "center",
+        }
+    }
+}
+
+/// Pencil marks the player writes while solving. Like `Board::colors` they have a
+/// bit for every number of a cell, bit `n - 1` for number `n`.
+impl Board {
+    fn pencil(&self, pencil: Pencil) -> &Vec<u32> {
+        match pencil {
+            Pencil::Corner => &self.corner_marks,
+            Pencil::Center => &self.center_marks,
+        }
+    }
+
+    fn pencil_mut(&mut self, pencil: Pencil) -> &mut Vec<u32> {
+        match pencil {
+            Pencil::Corner => &mut self.corner_marks,
+            Pencil::Center => &mut self.center_marks,
+        }
+    }
+
+    /// write `num` in cell `index`, or rub it out if it's already there
+    pub fn toggle_pencil(&mut self, pencil: Pencil, index: usize, num: u8) {
+        self.pencil_mut(pencil)[index] ^= 1 << (num - 1);
+    }
+
+    /// the `pencil` marks of cell `index`, lowest first
+    pub fn pencil_marks(&self, pencil: Pencil, index: usize) -> Vec<u8> {
+        let marks = self.pencil(pencil)[index];
+        (1..=self.width() as u8)
+            .filter(|num| marks & 1 << (num - 1) != 0)
+            .collect()
+    }
+
+    /// rub out every pencil mark of cell `index`
+    pub fn clear_pencil(&mut self, index: usize) {
+        self.corner_marks[index] = 0;
+        self.center_marks[index] = 0;
+    }
+
+    /// `pencil` marks written like `Board::colors_line`, `None` without any
+    pub fn pencil_line(&self, pencil: Pencil) -> Option<String> {
+        cells_line(self.size(), |i| self.pencil_marks(pencil, i))
+    }
+
+    /// read marks written by `pencil_line`, skipping what doesn't fit the board
+    pub fn read_pencil<'a>(&mut self, pencil: Pencil, words: impl IntoIterator<Item = &'a str>) {
+        let (size, max) = (self.size(), self.width() as u8);
+        let marks = self.pencil_mut(pencil);
+        read_cells_line(words, size, max, |cell, num| marks[cell] |= 1 << (num - 1));
+    }
+}
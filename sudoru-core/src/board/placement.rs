@@ -0,0 +1,82 @@
+use super::*;
+
+/// Failed to place a number in a cell
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum PlaceError {
+    AlreadyInCell,
+    AlreadyInRow,
+    AlreadyInCol,
+    AlreadyInSeg,
+    /// X-Sudoku only
+    AlreadyInDiagonal,
+    /// Killer Sudoku only
+    AlreadyInCage,
+    /// the cage can't add up to its sum anymore
+    CageSum,
+    /// one of the board's `constraints`, see `Board::constraint_conflict`
+    Constraint,
+    /// the cell is part of the puzzle
+    Given,
+}
+
+impl Board {
+    pub fn placement_error(&self, pos: impl Position, cell: Cell) -> Option<PlaceError> {
+        let index = self.index(pos);
+
+        if self.givens[index] {
+            return Some(PlaceError::Given);
+        }
+        if self.cells[index] == cell {
+            return Some(PlaceError::AlreadyInCell);
+        }
+        if self.row(index).contains(&cell) {
+            return Some(PlaceError::AlreadyInRow);
+        }
+        if self.col(index).contains(&cell) {
+            return Some(PlaceError::AlreadyInCol);
+        }
+        if self.segment(index).contains(&cell) {
+            return Some(PlaceError::AlreadyInSeg);
+        }
+        if self
+            .diagonal_cells(index)
+            .into_iter()
+            .any(|i| self.cells[i] == cell)
+        {
+            return Some(PlaceError::AlreadyInDiagonal);
+        }
+        if let (Some(cage), Some(num)) = (self.cage(index), cell) {
+            if cage.cells.iter().any(|&i| self.cells[i] == cell) {
+                return Some(PlaceError::AlreadyInCage);
+            }
+            if cage.fitting_in(self, index) & 1 << (num - 1) == 0 {
+                return Some(PlaceError::CageSum);
+            }
+        }
+        if let Some(num) = cell
+            && self.constraint_conflict(index, num).is_some()
+        {
+            return Some(PlaceError::Constraint);
+        }
+        None
+    }
+
+    pub fn place(&mut self, pos: impl Position, cell: Cell) -> Result<(), PlaceError> {
+        match self.placement_error(pos, cell) {
+            Some(e) => Err(e),
+            None => {
+                let index = self.index(pos);
+                self.cells[index] = cell;
+                Ok(())
+            }
+        }
+    }
+    /// set a cell without checking any rules
+    pub fn set(&mut self, pos: impl Position, cell: Cell) {
+        let index = self.index(pos);
+        self.cells[index] = cell;
+    }
+    pub fn place_at(&mut self, pos: impl Position, cell: Cell) -> Result<(), PlaceError> {
+        self.place(pos, cell)
+    }
+}
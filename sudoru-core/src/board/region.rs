@@ -0,0 +1,90 @@
+use rand::{Rng, seq::IndexedRandom};
+
+use super::*;
+
+/// tries at moving a cell to another region, per cell on the board
+const SWAPS: usize = 20;
+
+/// Jigsaw Sudoku: segments can be any connected regions of `width` cells,
+/// numbered from 0 and stored for every cell in `Board::regions`
+impl Board {
+    /// every region has `width` cells and they're all connected
+    pub fn is_layout(&self, regions: &[usize]) -> bool {
+        let side = self.width();
+        regions.len() == self.size()
+            && (0..side).all(|region| {
+                let count = regions.iter().filter(|&&r| r == region).count();
+                count == side && self.connected(regions, region)
+            })
+    }
+
+    /// the cells of `region` can all be reached from each other going left, right, up and down
+    pub(super) fn connected(&self, regions: &[usize], region: usize) -> bool {
+        let Some(start) = regions.iter().position(|&r| r == region) else {
+            return true;
+        };
+        let mut seen = vec![false; self.size()];
+        seen[start] = true;
+        let mut todo = vec![start];
+        let mut count = 0;
+
+        while let Some(i) = todo.pop() {
+            count += 1;
+            for next in self.neighbours(i) {
+                if regions[next] == region && !seen[next] {
+                    seen[next] = true;
+                    todo.push(next);
+                }
+            }
+        }
+        count == regions.iter().filter(|&&r| r == region).count()
+    }
+
+    /// A random layout that this filled board still solves, made by swapping cells
+    /// with the same number between neighbouring regions, starting from its segments
+    pub fn random_regions(&self, rng: &mut impl Rng) -> Vec<usize> {
+        let mut regions: Vec<usize> = (0..self.size()).map(|i| self.seg_index(i)).collect();
+
+        for _ in 0..self.size() * SWAPS {
+            // a moves into b's region, and the cell c of that region with a's number moves
+            // into a's, so both regions still hold every number
+            let a = rng.random_range(0..self.size());
+            let others: Vec<usize> = self
+                .neighbours(a)
+                .filter(|&b| regions[b] != regions[a])
+                .collect();
+            let Some(&b) = others.choose(rng) else {
+                continue;
+            };
+            let (from, to) = (regions[a], regions[b]);
+            let Some(c) =
+                (0..self.size()).find(|&c| regions[c] == to && self.cells[c] == self.cells[a])
+            else {
+                continue;
+            };
+
+            regions[a] = to;
+            regions[c] = from;
+            if !self.connected(&regions, from) || !self.connected(&regions, to) {
+                regions[a] = from;
+                regions[c] = to;
+            }
+        }
+        regions
+    }
+
+    /// the region of every cell as the symbol of region + 1, like `line`
+    pub fn regions_line(&self) -> Option<String> {
+        let regions = self.regions.as_ref()?;
+        Some(regions.iter().map(|&r| symbol(r as u8 + 1)).collect())
+    }
+
+    /// read regions written by `regions_line`, `None` if they aren't a layout for this board
+    pub fn parse_regions(&self, line: &str) -> Option<Vec<usize>> {
+        let regions = line
+            .chars()
+            .map(|c| from_symbol(c).map(|r| r as usize - 1))
+            .collect::<Option<Vec<usize>>>()?;
+        self.is_layout(&regions).then_some(regions)
+    }
+}
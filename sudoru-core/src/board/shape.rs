@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+use super::*;
+
+/// The size of the segments a board is split into.
+/// A board is as wide and as tall as a segment has cells,
+/// so a 3×2 segment makes a 6×6 board with the numbers 1..=6.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Shape {
+    /// cells across one segment
+    pub seg_width: usize,
+    /// cells down one segment
+    pub seg_height: usize,
+}
+
+impl Shape {
+    pub const STANDARD: Shape = Shape::new(3, 3);
+
+    /// every supported board, smallest first
+    pub const ALL: [Shape; 6] = [
+        Shape::new(2, 2),
+        Shape::new(3, 2),
+        Shape::new(3, 3),
+        Shape::new(4, 3),
+        Shape::new(4, 4),
+        Shape::new(5, 5),
+    ];
+
+    pub const fn new(seg_width: usize, seg_height: usize) -> Self {
+        Self {
+            seg_width,
+            seg_height,
+        }
+    }
+
+    /// width and height of the board, and the highest number
+    pub const fn side(self) -> usize {
+        self.seg_width * self.seg_height
+    }
+
+    /// number of cells
+    pub const fn size(self) -> usize {
+        self.side() * self.side()
+    }
+
+    /// the shape of a supported board `side` cells wide
+    pub fn from_side(side: usize) -> Option<Shape> {
+        Self::ALL.into_iter().find(|shape| shape.side() == side)
+    }
+
+    /// the shape of a supported board with `size` cells
+    pub fn from_size(size: usize) -> Option<Shape> {
+        Self::ALL.into_iter().find(|shape| shape.size() == size)
+    }
+
+    /// the same board mirrored across its diagonal, with segments on their side
+    pub const fn transposed(self) -> Shape {
+        Shape::new(self.seg_height, self.seg_width)
+    }
+
+    /// round to the top-left cell of the segment, (1, 2) -> (0, 0) on a 9×9 board
+    pub fn segment(self, pos: impl Position) -> (usize, usize) {
+        let (x, y) = pos.coords(self.side());
+        (x - x % self.seg_width, y - y % self.seg_height)
+    }
+
+    /// segments are numbered left to right, top to bottom
+    pub fn seg_index(self, pos: impl Position) -> usize {
+        let (x, y) = pos.coords(self.side());
+        x / self.seg_width + y / self.seg_height * (self.side() / self.seg_width)
+    }
+
+    /// every row, column and segment as lists of cell indices
+    pub fn units(self) -> Vec<Vec<usize>> {
+        let side = self.side();
+        let mut units = Vec::with_capacity(side * 3);
+
+        for i in 0..side {
+            units.push((0..side).map(|j| (j, i).index(side)).collect());
+            units.push((0..side).map(|j| (i, j).index(side)).collect());
+            units.push(self.seg_cells(i).collect());
+        }
+        units
+    }
+
+    /// the cells of segment `seg`, left to right, top to bottom
+    pub fn seg_cells(self, seg: usize) -> impl Iterator<Item = usize> {
+        let stacks = self.side() / self.seg_width;
+        let (x, y) = (
+            seg % stacks * self.seg_width,
+            seg / stacks * self.seg_height,
+        );
+        (0..self.side())
+            .map(move |j| (x + j % self.seg_width, y + j / self.seg_width).index(self.side()))
+    }
+}
+
+impl Default for Shape {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+impl Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{0}x{0}", self.side())
+    }
+}
+
+impl FromStr for Shape {
+    type Err = String;
+
+    /// the side of the board, like `6` or `6x6`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let side = match s.split_once(['x', '×']) {
+            Some((width, height)) if width == height => width,
+            Some(_) => return Err(format!("{s:?} isn't square")),
+            None => s,
+        };
+        side.parse()
+            .ok()
+            .and_then(Shape::from_side)
+            .ok_or_else(|| format!("no {s} board, sizes are 4, 6, 9, 12, 16 and 25"))
+    }
+}
@@ -0,0 +1,270 @@
+use rand::{Rng, seq::SliceRandom};
+
+use super::*;
+use crate::constraint::Mark;
+
+/// Why a transformation would turn a valid board into one that isn't
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransformError {
+    /// relabelling changes cage sums and constraints on numbers, like parity or thermometers
+    Relabel,
+    /// moving rows or columns breaks the diagonals, constraints of the whole board like
+    /// anti-knight, or regions, cages and lines that have to stay in one piece
+    Move,
+}
+
+impl Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Relabel => write!(f, "relabelling would break the cages or constraints"),
+            Self::Move => write!(f, "moving rows or columns would break the variant's rules"),
+        }
+    }
+}
+
+/// Transformations that turn a valid board into another valid board.
+/// Givens move along with their cells.
+/// Cages and jigsaw regions move along too. Rotations, flips and transposing keep
+/// the diagonals on the diagonals, moving rows and columns doesn't, so that's only valid
+/// without `diagonals`, and only if regions, cages and lines stay in one piece.
+/// Constraints on cells move along, but only rotations, flips and transposing keep
+/// others like anti-knight or windoku. Windoku boxes only sit the same way round on
+/// square segments though, so there rotations and flips are refused otherwise.
+/// Relabelling changes sums, so it's only valid without cages or constraints on numbers.
+impl Board {
+    /// move every cell at (x, y) to `to(x, y)` on a board of `shape`
+    fn moved(&self, shape: Shape, to: impl Fn(usize, usize) -> (usize, usize)) -> Board {
+        let mut board = Board {
+            diagonals: self.diagonals,
+            ..Board::with_shape(shape)
+        };
+        let dests: Vec<usize> = (0..self.size())
+            .map(|index| {
+                let (x, y) = self.coords(index);
+                board.index(to(x, y))
+            })
+            .collect();
+        for (index, &dest) in dests.iter().enumerate() {
+            board.cells[dest] = self.cells[index];
+            board.givens[dest] = self.givens[index];
+            board.colors[dest] = self.colors[index];
+            board.corner_marks[dest] = self.corner_marks[index];
+            board.center_marks[dest] = self.center_marks[index];
+        }
+        if let Some(regions) = &self.regions {
+            let mut moved = vec![0; self.size()];
+            for (index, &dest) in dests.iter().enumerate() {
+                moved[dest] = regions[index];
+            }
+            board.regions = Some(moved);
+        }
+        board.constraints = self
+            .constraints
+            .iter()
+            .map(|c| c.moved(&dests).unwrap_or_else(|| c.clone()))
+            .collect();
+        board.cages = self
+            .cages
+            .iter()
+            .map(|cage| Cage::new(cage.cells.iter().map(|&i| dests[i]).collect(), cage.sum))
+            .collect();
+        board.cages.sort_by_key(Cage::corner);
+        board
+    }
+
+    /// Like `moved` for rotations and flips, as long as the units of rules of the
+    /// whole board, like windoku boxes, end up where the rule puts them again
+    fn turned(
+        &self,
+        shape: Shape,
+        to: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Result<Board, TransformError> {
+        let board = self.moved(shape, &to);
+        let unmoved: Vec<usize> = (0..self.size()).collect();
+        let sorted = |units: Vec<Vec<usize>>| {
+            let mut units: Vec<Vec<usize>> = units
+                .into_iter()
+                .map(|mut unit| {
+                    unit.sort();
+                    unit
+                })
+                .collect();
+            units.sort();
+            units
+        };
+        let dest = |index: usize| {
+            let (x, y) = self.coords(index);
+            board.index(to(x, y))
+        };
+
+        let kept = self
+            .constraints
+            .iter()
+            .filter(|c| c.moved(&unmoved).is_none())
+            .all(|c| {
+                let units = c.units(self).into_iter();
+                let moved = units.map(|unit| unit.into_iter().map(dest).collect());
+                sorted(moved.collect()) == sorted(c.units(&board))
+            });
+        if !kept {
+            return Err(TransformError::Move);
+        }
+        Ok(board)
+    }
+
+    /// Like `moved` for moving rows inside their bands and whole bands,
+    /// as long as nothing that depends on where cells are breaks
+    fn rearranged(
+        &self,
+        to: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Result<Board, TransformError> {
+        let unmoved: Vec<usize> = (0..self.size()).collect();
+        // rules of the whole board, like windoku, don't move along
+        if self.diagonals || self.constraints.iter().any(|c| c.moved(&unmoved).is_none()) {
+            return Err(TransformError::Move);
+        }
+
+        let board = self.moved(self.shape, to);
+        let regions = board.regions.as_ref().is_none_or(|r| board.is_layout(r));
+        let cages = board.cages.iter().all(|cage| {
+            let mut cells = vec![0; board.size()];
+            for &i in &cage.cells {
+                cells[i] = 1;
+            }
+            board.connected(&cells, 1)
+        });
+        let lines = board
+            .constraints
+            .iter()
+            .flat_map(|c| c.marks(&board))
+            .all(|mark| {
+                let touching = |pair: &[usize]| {
+                    let ((ax, ay), (bx, by)) = (board.coords(pair[0]), board.coords(pair[1]));
+                    ax.abs_diff(bx) <= 1 && ay.abs_diff(by) <= 1
+                };
+                match mark {
+                    Mark::Thermometer(cells) | Mark::Arrow(cells) => cells.windows(2).all(touching),
+                    Mark::Edge(_, a, b) => board.neighbours(a).any(|n| n == b),
+                    _ => true,
+                }
+            });
+
+        if !(regions && cages && lines) {
+            return Err(TransformError::Move);
+        }
+        Ok(board)
+    }
+
+    /// a quarter turn clockwise, segments that aren't square end up on their side
+    pub fn rotated(&self) -> Result<Board, TransformError> {
+        let bottom = self.height() - 1;
+        self.turned(self.shape.transposed(), |x, y| (bottom - y, x))
+    }
+
+    /// mirrored across the top-left to bottom-right diagonal,
+    /// which keeps every rule, windoku boxes included
+    pub fn transposed(&self) -> Board {
+        self.moved(self.shape.transposed(), |x, y| (y, x))
+    }
+
+    /// turned upside down, mirrored across the middle row
+    pub fn flipped_vertically(&self) -> Result<Board, TransformError> {
+        let bottom = self.height() - 1;
+        self.turned(self.shape, |x, y| (x, bottom - y))
+    }
+
+    /// mirrored across the middle column
+    pub fn flipped_horizontally(&self) -> Result<Board, TransformError> {
+        let right = self.width() - 1;
+        self.turned(self.shape, |x, y| (right - x, y))
+    }
+
+    /// swap rows `a` and `b` (0..segment height) inside `band`
+    pub fn swap_rows(&self, band: usize, a: usize, b: usize) -> Result<Board, TransformError> {
+        let rows = self.shape.seg_height;
+        let (a, b) = (band * rows + a, band * rows + b);
+        self.rearranged(|x, y| (x, swapped(y, a, b)))
+    }
+
+    /// swap columns `a` and `b` (0..segment width) inside `stack`
+    pub fn swap_cols(&self, stack: usize, a: usize, b: usize) -> Result<Board, TransformError> {
+        Ok(self.transposed().swap_rows(stack, a, b)?.transposed())
+    }
+
+    /// swap whole bands of rows
+    pub fn swap_bands(&self, a: usize, b: usize) -> Result<Board, TransformError> {
+        let rows = self.shape.seg_height;
+        self.rearranged(|x, y| {
+            let band = swapped(y / rows, a, b);
+            (x, band * rows + y % rows)
+        })
+    }
+
+    /// swap whole stacks of columns
+    pub fn swap_stacks(&self, a: usize, b: usize) -> Result<Board, TransformError> {
+        Ok(self.transposed().swap_bands(a, b)?.transposed())
+    }
+
+    /// every number `n` becomes `labels[n - 1]`,
+    /// which has to be a permutation of the board's numbers
+    pub fn relabelled(&self, labels: &[u8]) -> Result<Board, TransformError> {
+        if !self.cages.is_empty() || !self.constraints.iter().all(|c| c.ignores_numbers()) {
+            return Err(TransformError::Relabel);
+        }
+        let mut board = self.clone();
+        for cell in board.cells.iter_mut().flatten() {
+            *cell = labels[*cell as usize - 1];
+        }
+        Ok(board)
+    }
+
+    /// A random equivalent board: relabelled, maybe transposed if the segments are square,
+    /// with bands, stacks and the rows and columns inside them shuffled.
+    /// Variants only get the parts that keep them valid, jigsaws for example
+    /// often only whole bands and stacks, and diagonal boards only transposing.
+    pub fn shuffled(&self, rng: &mut impl Rng) -> Board {
+        let mut labels: Vec<u8> = (1..=self.width() as u8).collect();
+        labels.shuffle(rng);
+        let mut board = self.relabelled(&labels).unwrap_or_else(|_| self.clone());
+
+        if rng.random_bool(0.5) && self.shape.transposed() == self.shape {
+            board = board.transposed();
+        }
+
+        for _ in 0..2 {
+            let height = board.shape.seg_height;
+            let mut bands: Vec<usize> = (0..board.height() / height).collect();
+            bands.shuffle(rng);
+            let rows: Vec<Vec<usize>> = (0..bands.len())
+                .map(|_| {
+                    let mut rows: Vec<usize> = (0..height).collect();
+                    rows.shuffle(rng);
+                    rows
+                })
+                .collect();
+
+            let shuffled = board
+                .rearranged(|x, y| {
+                    let (band, row) = (y / height, y % height);
+                    (x, bands[band] * height + rows[band][row])
+                })
+                .or_else(|_| board.rearranged(|x, y| (x, bands[y / height] * height + y % height)));
+            if let Ok(shuffled) = shuffled {
+                board = shuffled;
+            }
+            // the second time around shuffles the columns
+            board = board.transposed();
+        }
+        board
+    }
+}
+
+fn swapped(i: usize, a: usize, b: usize) -> usize {
+    if i == a {
+        b
+    } else if i == b {
+        a
+    } else {
+        i
+    }
+}
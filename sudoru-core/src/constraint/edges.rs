@@ -0,0 +1,212 @@
+use super::*;
+
+/// How the numbers in two cells next to each other relate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EdgeKind {
+    /// a white Kropki dot: consecutive numbers
+    White,
+    /// a black Kropki dot: one number is double the other
+    Black,
+    /// adding up to 10
+    X,
+    /// adding up to 5
+    V,
+    /// the first number is smaller, shown as `<` pointing at it
+    Less,
+}
+
+impl EdgeKind {
+    /// the word for the kind in a constraint
+    pub fn word(self) -> &'static str {
+        match self {
+            EdgeKind::White => "white",
+            EdgeKind::Black => "black",
+            EdgeKind::X => "x",
+            EdgeKind::V => "v",
+            EdgeKind::Less => "less",
+        }
+    }
+
+    /// the name of the constraint, the same as the word except for comparisons
+    pub fn name(self) -> &'static str {
+        match self {
+            EdgeKind::Less => "greater-than",
+            kind => kind.word(),
+        }
+    }
+
+    fn from_word(word: &str) -> Option<EdgeKind> {
+        use EdgeKind::*;
+        [White, Black, X, V, Less]
+            .into_iter()
+            .find(|kind| kind.word() == word)
+    }
+
+    /// whether `a` in the first cell and `b` in the second fit
+    pub fn holds(self, a: u8, b: u8) -> bool {
+        match self {
+            EdgeKind::White => a.abs_diff(b) == 1,
+            EdgeKind::Black => a == b * 2 || b == a * 2,
+            EdgeKind::X => a + b == 10,
+            EdgeKind::V => a + b == 5,
+            EdgeKind::Less => a < b,
+        }
+    }
+}
+
+/// A marker on the edge between two cells beside, above or below each other
+#[derive(Debug)]
+pub struct Edge {
+    pub kind: EdgeKind,
+    pub cells: [usize; 2],
+}
+
+impl Edge {
+    /// Greater-than Sudoku: a comparison between every two touching cells
+    /// of the same segment, the way round the numbers of the filled `board` are
+    pub fn comparisons(board: &Board) -> Vec<Arc<dyn Constraint>> {
+        let mut comparisons: Vec<Arc<dyn Constraint>> = Vec::new();
+        for a in 0..board.size() {
+            let Some(num) = board.cells[a] else {
+                continue;
+            };
+            for b in board.neighbours(a).filter(|&b| b > a) {
+                if board.seg_index(a) != board.seg_index(b) {
+                    continue;
+                }
+                let Some(other) = board.cells[b] else {
+                    continue;
+                };
+                let cells = if num < other { [a, b] } else { [b, a] };
+                comparisons.push(Arc::new(Edge {
+                    kind: EdgeKind::Less,
+                    cells,
+                }));
+            }
+        }
+        comparisons
+    }
+
+    /// `cell,cell` for two cells that touch on a board of `size` cells
+    pub fn parse(kind: &str, rest: &str, size: usize) -> Option<Edge> {
+        let kind = EdgeKind::from_word(kind)?;
+        let side = Shape::from_size(size)?.side();
+        let (a, b) = rest.split_once(',')?;
+        let (a, b): (usize, usize) = (a.parse().ok()?, b.parse().ok()?);
+        let touching = (a / side == b / side && a.abs_diff(b) == 1) || a.abs_diff(b) == side;
+        (a < size && b < size && touching).then_some(Edge {
+            kind,
+            cells: [a, b],
+        })
+    }
+}
+
+impl Constraint for Edge {
+    fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    fn allowed(&self, board: &Board, index: usize) -> Candidates {
+        let side = board.width() as u8;
+        let Some(k) = self.cells.iter().position(|&i| i == index) else {
+            return solve::all(side as usize);
+        };
+        let others: Vec<u8> = match board.cells[self.cells[1 - k]] {
+            Some(num) => vec![num],
+            None => (1..=side).collect(),
+        };
+
+        let mut allowed = 0;
+        for num in 1..=side {
+            let fits = |&other: &u8| match k {
+                0 => self.kind.holds(num, other),
+                _ => self.kind.holds(other, num),
+            };
+            if others.iter().filter(|&&other| other != num).any(fits) {
+                allowed |= 1 << (num - 1);
+            }
+        }
+        allowed
+    }
+
+    fn marks(&self, _board: &Board) -> Vec<Mark> {
+        vec![Mark::Edge(self.kind, self.cells[0], self.cells[1])]
+    }
+
+    fn moved(&self, dests: &[usize]) -> Option<Arc<dyn Constraint>> {
+        Some(Arc::new(Edge {
+            kind: self.kind,
+            cells: self.cells.map(|i| dests[i]),
+        }))
+    }
+}
+
+/// The negative constraint: every marker of these kinds is given,
+/// so touching cells without one don't fit any of them
+#[derive(Debug)]
+pub struct Negative {
+    pub kinds: [EdgeKind; 2],
+}
+
+impl Negative {
+    pub const KROPKI: Negative = Negative {
+        kinds: [EdgeKind::White, EdgeKind::Black],
+    };
+    pub const XV: Negative = Negative {
+        kinds: [EdgeKind::X, EdgeKind::V],
+    };
+}
+
+impl Constraint for Negative {
+    fn name(&self) -> &'static str {
+        match self.kinds[0] {
+            EdgeKind::White | EdgeKind::Black => "kropki-negative",
+            _ => "xv-negative",
+        }
+    }
+
+    fn allowed(&self, board: &Board, index: usize) -> Candidates {
+        // the cells that share a marker of these kinds with this one
+        let marked: Vec<usize> = board
+            .constraints
+            .iter()
+            .flat_map(|c| c.marks(board))
+            .filter_map(|mark| match mark {
+                Mark::Edge(kind, a, b) if self.kinds.contains(&kind) => {
+                    (a == index).then_some(b).or((b == index).then_some(a))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut allowed = solve::all(board.width());
+        for neighbour in board.neighbours(index) {
+            let Some(other) = board.cells[neighbour] else {
+                continue;
+            };
+            if marked.contains(&neighbour) {
+                continue;
+            }
+            for num in 1..=board.width() as u8 {
+                if self.kinds.iter().any(|kind| kind.holds(num, other)) {
+                    allowed &= !(1 << (num - 1));
+                }
+            }
+        }
+        allowed
+    }
+}
+
+impl Display for Edge {
+    /// `white:cell,cell`, `black:...`, `x:...`, `v:...` or `less:...` with the smaller first
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_cells(f, self.kind.word(), &self.cells)
+    }
+}
+
+impl Display for Negative {
+    /// `kropki-negative` or `xv-negative`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
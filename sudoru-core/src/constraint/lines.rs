@@ -0,0 +1,237 @@
+use super::*;
+
+/// Numbers get larger along the thermometer, starting from the bulb in the first cell
+#[derive(Debug)]
+pub struct Thermometer {
+    pub cells: Vec<usize>,
+}
+
+impl Constraint for Thermometer {
+    fn name(&self) -> &'static str {
+        "thermo"
+    }
+
+    fn allowed(&self, board: &Board, index: usize) -> Candidates {
+        let side = board.width();
+        let Some(k) = self.cells.iter().position(|&i| i == index) else {
+            return solve::all(side);
+        };
+
+        // room for the smaller numbers before and the larger ones after
+        let mut low = k as isize + 1;
+        let mut high = (side - (self.cells.len() - 1 - k)) as isize;
+        for (p, &i) in self.cells.iter().enumerate() {
+            let Some(num) = board.cells[i] else {
+                continue;
+            };
+            let (num, distance) = (num as isize, p.abs_diff(k) as isize);
+            if p < k {
+                low = low.max(num + distance);
+            } else if p > k {
+                high = high.min(num - distance);
+            }
+        }
+        between(low, high, side)
+    }
+
+    fn marks(&self, _board: &Board) -> Vec<Mark> {
+        vec![Mark::Thermometer(self.cells.clone())]
+    }
+
+    fn moved(&self, dests: &[usize]) -> Option<Arc<dyn Constraint>> {
+        let cells = self.cells.iter().map(|&i| dests[i]).collect();
+        Some(Arc::new(Thermometer { cells }))
+    }
+}
+
+/// The numbers along the arrow add up to the number in its circle, the first cell.
+/// They can repeat, unless something else keeps them apart.
+#[derive(Debug)]
+pub struct Arrow {
+    pub cells: Vec<usize>,
+}
+
+impl Constraint for Arrow {
+    fn name(&self) -> &'static str {
+        "arrow"
+    }
+
+    fn allowed(&self, board: &Board, index: usize) -> Candidates {
+        let side = board.width();
+        let Some(k) = self.cells.iter().position(|&i| i == index) else {
+            return solve::all(side);
+        };
+
+        // what the rest of the arrow adds up to so far, and how many of its cells are empty
+        let (mut sum, mut empty) = (0, 0);
+        for &i in self.cells[1..].iter().filter(|&&i| i != index) {
+            match board.cells[i] {
+                Some(num) => sum += num as isize,
+                None => empty += 1,
+            }
+        }
+        let side = side as isize;
+
+        if k == 0 {
+            return between(sum + empty, sum + empty * side, side as usize);
+        }
+        match board.cells[self.cells[0]] {
+            Some(circle) if empty == 0 => {
+                let rest = circle as isize - sum;
+                between(rest, rest, side as usize)
+            }
+            Some(circle) => between(1, circle as isize - sum - empty, side as usize),
+            None => between(1, side - sum - empty, side as usize),
+        }
+    }
+
+    fn marks(&self, _board: &Board) -> Vec<Mark> {
+        vec![Mark::Arrow(self.cells.clone())]
+    }
+
+    fn moved(&self, dests: &[usize]) -> Option<Arc<dyn Constraint>> {
+        let cells = self.cells.iter().map(|&i| dests[i]).collect();
+        Some(Arc::new(Arrow { cells }))
+    }
+}
+
+/// The numbers between the 1 and the highest number of a row or column add up to `sum`
+#[derive(Debug)]
+pub struct Sandwich {
+    /// a column instead of a row
+    pub col: bool,
+    /// which row or column, from 0
+    pub line: usize,
+    pub sum: u32,
+}
+
+impl Sandwich {
+    /// `r2:15` for row 2 or `c4:0` for column 4, on a board `side` cells wide
+    pub fn parse(s: &str, side: usize) -> Option<Sandwich> {
+        let (line, sum) = s.split_once(':')?;
+        let col = match line.chars().next()? {
+            'r' => false,
+            'c' => true,
+            _ => return None,
+        };
+        Some(Sandwich {
+            col,
+            line: line[1..].parse().ok().filter(|&line| line < side)?,
+            sum: sum.parse().ok()?,
+        })
+    }
+
+    fn cells(&self, side: usize) -> Vec<usize> {
+        (0..side)
+            .map(|j| match self.col {
+                true => self.line + j * side,
+                false => j + self.line * side,
+            })
+            .collect()
+    }
+
+    /// Whether the cells between positions `a` and `b` of the line, with `num` in `index`,
+    /// can still add up to the sum. They hold numbers from 2 to one below the highest.
+    fn fits(
+        &self,
+        board: &Board,
+        cells: &[usize],
+        (a, b): (usize, usize),
+        index: usize,
+        num: u8,
+    ) -> bool {
+        let (mut sum, mut empty) = (0, 0);
+        for &i in &cells[a.min(b) + 1..a.max(b)] {
+            match board.cells[i] {
+                _ if i == index => sum += num as u32,
+                Some(n) => sum += n as u32,
+                None => empty += 1,
+            }
+        }
+        let highest = board.width() as u32 - 1;
+        match empty {
+            0 => sum == self.sum,
+            _ => sum + empty * 2 <= self.sum && self.sum <= sum + empty * highest,
+        }
+    }
+}
+
+impl Constraint for Sandwich {
+    fn name(&self) -> &'static str {
+        "sandwich"
+    }
+
+    fn allowed(&self, board: &Board, index: usize) -> Candidates {
+        let side = board.width();
+        let cells = self.cells(side);
+        let Some(k) = cells.iter().position(|&i| i == index) else {
+            return solve::all(side);
+        };
+        let find = |num| {
+            cells
+                .iter()
+                .position(|&i| i != index && board.cells[i] == Some(num))
+        };
+        let (one, top) = (find(1), find(side as u8));
+
+        let mut allowed = 0;
+        for num in 1..=side as u8 {
+            let one = if num == 1 { Some(k) } else { one };
+            let top = if num == side as u8 { Some(k) } else { top };
+            let fits = match (one, top) {
+                (Some(a), Some(b)) => self.fits(board, &cells, (a, b), index, num),
+                _ => true,
+            };
+            if fits {
+                allowed |= 1 << (num - 1);
+            }
+        }
+        allowed
+    }
+
+    fn marks(&self, _board: &Board) -> Vec<Mark> {
+        match self.col {
+            true => vec![Mark::ColClue(self.line, self.sum)],
+            false => vec![Mark::RowClue(self.line, self.sum)],
+        }
+    }
+
+    fn moved(&self, dests: &[usize]) -> Option<Arc<dyn Constraint>> {
+        let side = dests.len().isqrt();
+        let cells: Vec<usize> = self.cells(side).iter().map(|&i| dests[i]).collect();
+        // the line ends up as a row or column, the sum doesn't care which way round
+        let col = cells[0] % side == cells[1] % side;
+        let line = if col {
+            cells[0] % side
+        } else {
+            cells[0] / side
+        };
+        Some(Arc::new(Sandwich {
+            col,
+            line,
+            sum: self.sum,
+        }))
+    }
+}
+
+impl Display for Thermometer {
+    /// `thermo:cell,cell,...`, starting from the bulb
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_cells(f, self.name(), &self.cells)
+    }
+}
+
+impl Display for Arrow {
+    /// `arrow:cell,cell,...`, starting from the circle
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_cells(f, self.name(), &self.cells)
+    }
+}
+
+impl Display for Sandwich {
+    /// `sandwich:r2:15` for row 2 or `sandwich:c4:0` for column 4
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = if self.col { 'c' } else { 'r' };
+        write!(f, "{}:{kind}{}:{}", self.name(), self.line, self.sum)
+    }
+}
@@ -0,0 +1,416 @@
+mod edges;
+mod lines;
+
+pub use edges::{Edge, EdgeKind, Negative};
+pub use lines::{Arrow, Sandwich, Thermometer};
+
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+use crate::board::{Board, Shape};
+use crate::solve::{self, Candidates};
+
+/// An extra rule on top of rows, columns and segments, any number of which
+/// can be added to a board's `constraints`.
+/// Every method has a default that doesn't restrict anything,
+/// so a constraint only implements the ways it narrows down numbers.
+///
+/// `Display` writes it the way `parse` reads it, in a word without spaces.
+pub trait Constraint: Debug + Display + Send + Sync {
+    /// a short name to show while playing, like `anti-knight`
+    fn name(&self) -> &'static str;
+
+    /// groups of cells that hold every number once, like windoku boxes
+    fn units(&self, _board: &Board) -> Vec<Vec<usize>> {
+        Vec::new()
+    }
+
+    /// other cells that can't hold the same number as cell `index`
+    fn peers(&self, _board: &Board, _index: usize) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// numbers cell `index` can hold, next to the numbers already on `board`
+    fn allowed(&self, board: &Board, _index: usize) -> Candidates {
+        solve::all(board.width())
+    }
+
+    /// what to draw on the board to show the constraint
+    fn marks(&self, _board: &Board) -> Vec<Mark> {
+        Vec::new()
+    }
+
+    /// The constraint after every cell `i` moved to `dests[i]`,
+    /// `None` if it doesn't refer to any cells
+    fn moved(&self, _dests: &[usize]) -> Option<Arc<dyn Constraint>> {
+        None
+    }
+
+    /// whether it only cares which cells hold the same number, like anti-knight,
+    /// so it still holds after relabelling the numbers
+    fn ignores_numbers(&self) -> bool {
+        false
+    }
+}
+
+impl PartialEq for dyn Constraint {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
+/// Something to draw on the board, leaving the actual look to the renderer
+#[derive(Clone, Debug, PartialEq)]
+pub enum Mark {
+    /// a shaded background
+    Shade(usize),
+    /// a circle behind the number
+    Circle(usize),
+    /// a square behind the number
+    Square(usize),
+    /// a thermometer through the cells, with the bulb in the first
+    Thermometer(Vec<usize>),
+    /// an arrow through the cells, coming out of a circle in the first
+    Arrow(Vec<usize>),
+    /// a number next to a row, outside the board
+    RowClue(usize, u32),
+    /// a number next to a column, outside the board
+    ColClue(usize, u32),
+    /// a dot or letter on the edge between two cells
+    Edge(EdgeKind, usize, usize),
+    /// a background of this red, green and blue
+    Color(usize, [u8; 3]),
+}
+
+/// Read a constraint written by its `Display`.
+/// Constraints on cells only check that the cells are on a board of `size` cells.
+pub fn parse(word: &str, size: usize) -> Option<Arc<dyn Constraint>> {
+    let constraint: Arc<dyn Constraint> = match word {
+        "anti-knight" => Arc::new(AntiKnight),
+        "anti-king" => Arc::new(AntiKing),
+        "non-consecutive" => Arc::new(NonConsecutive),
+        "windoku" => Arc::new(Windoku),
+        "kropki-negative" => Arc::new(Negative::KROPKI),
+        "xv-negative" => Arc::new(Negative::XV),
+        _ => {
+            let (name, rest) = word.split_once(':')?;
+            match name {
+                "sandwich" => {
+                    let side = Shape::from_size(size)?.side();
+                    return Some(Arc::new(Sandwich::parse(rest, side)?));
+                }
+                "white" | "black" | "x" | "v" | "less" => {
+                    return Some(Arc::new(Edge::parse(name, rest, size)?));
+                }
+                "color" => return Some(Arc::new(Colored::parse(rest, size)?)),
+                _ => (),
+            }
+            let cells = rest
+                .split(',')
+                .map(|cell| cell.parse().ok().filter(|&i| i < size))
+                .collect::<Option<Vec<usize>>>()?;
+            match name {
+                "even" => Arc::new(Parity { odd: false, cells }),
+                "odd" => Arc::new(Parity { odd: true, cells }),
+                "thermo" if cells.len() >= 2 => Arc::new(Thermometer { cells }),
+                "arrow" if cells.len() >= 2 => Arc::new(Arrow { cells }),
+                _ => return None,
+            }
+        }
+    };
+    Some(constraint)
+}
+
+/// the numbers `low..=high` on a board with numbers up to `side`, none if `high < low`
+fn between(low: isize, high: isize, side: usize) -> Candidates {
+    let (low, high) = (low.max(1), high.min(side as isize));
+    (low..=high).fold(0, |candidates, num| candidates | 1 << (num - 1))
+}
+
+/// cells written as `cell,cell,...`
+fn write_cells(f: &mut std::fmt::Formatter<'_>, name: &str, cells: &[usize]) -> std::fmt::Result {
+    let cells: Vec<String> = cells.iter().map(usize::to_string).collect();
+    write!(f, "{name}:{}", cells.join(","))
+}
+
+/// every cell `moves` away from `index` that's on the board
+fn moves_away(board: &Board, index: usize, moves: &[(isize, isize)]) -> Vec<usize> {
+    let side = board.width() as isize;
+    let (x, y) = board.coords(index);
+    moves
+        .iter()
+        .map(|&(dx, dy)| (x as isize + dx, y as isize + dy))
+        .filter(|&(x, y)| (0..side).contains(&x) && (0..side).contains(&y))
+        .map(|(x, y)| board.index((x as usize, y as usize)))
+        .collect()
+}
+
+/// cells a knight's move apart can't hold the same number
+#[derive(Debug)]
+pub struct AntiKnight;
+
+impl Constraint for AntiKnight {
+    fn name(&self) -> &'static str {
+        "anti-knight"
+    }
+
+    fn peers(&self, board: &Board, index: usize) -> Vec<usize> {
+        let moves = [
+            (1, 2),
+            (2, 1),
+            (2, -1),
+            (1, -2),
+            (-1, -2),
+            (-2, -1),
+            (-2, 1),
+            (-1, 2),
+        ];
+        moves_away(board, index, &moves)
+    }
+
+    fn ignores_numbers(&self) -> bool {
+        true
+    }
+}
+
+/// touching cells, diagonals included, can't hold the same number
+#[derive(Debug)]
+pub struct AntiKing;
+
+impl Constraint for AntiKing {
+    fn name(&self) -> &'static str {
+        "anti-king"
+    }
+
+    fn peers(&self, board: &Board, index: usize) -> Vec<usize> {
+        // the cells beside, above and below are in the same row or column anyway
+        moves_away(board, index, &[(1, 1), (1, -1), (-1, 1), (-1, -1)])
+    }
+
+    fn ignores_numbers(&self) -> bool {
+        true
+    }
+}
+
+/// cells beside, above or below each other can't hold consecutive numbers
+#[derive(Debug)]
+pub struct NonConsecutive;
+
+impl Constraint for NonConsecutive {
+    fn name(&self) -> &'static str {
+        "non-consecutive"
+    }
+
+    fn allowed(&self, board: &Board, index: usize) -> Candidates {
+        let mut allowed = solve::all(board.width());
+        for neighbour in board.neighbours(index) {
+            if let Some(num) = board.cells[neighbour] {
+                // bit `num - 1` is `num`, so these are `num + 1` and `num - 1`
+                allowed &= !((1 << num) | (1 << (num - 1) >> 1));
+            }
+        }
+        allowed
+    }
+}
+
+/// Windoku or hyper sudoku: boxes the size of a segment, one cell in from
+/// the segments around them, hold every number once too
+#[derive(Debug)]
+pub struct Windoku;
+
+impl Constraint for Windoku {
+    fn name(&self) -> &'static str {
+        "windoku"
+    }
+
+    fn units(&self, board: &Board) -> Vec<Vec<usize>> {
+        let (width, height) = (board.shape.seg_width, board.shape.seg_height);
+        let starts = |size: usize| {
+            (1..board.width())
+                .step_by(size + 1)
+                .filter(move |s| s + size < board.width())
+        };
+
+        let mut units = Vec::new();
+        for y in starts(height) {
+            for x in starts(width) {
+                let cells =
+                    (0..width * height).map(|j| board.index((x + j % width, y + j / width)));
+                units.push(cells.collect());
+            }
+        }
+        units
+    }
+
+    fn marks(&self, board: &Board) -> Vec<Mark> {
+        self.units(board)
+            .into_iter()
+            .flatten()
+            .map(Mark::Shade)
+            .collect()
+    }
+
+    fn ignores_numbers(&self) -> bool {
+        true
+    }
+}
+
+/// cells that hold only even or only odd numbers
+#[derive(Debug)]
+pub struct Parity {
+    pub odd: bool,
+    pub cells: Vec<usize>,
+}
+
+impl Constraint for Parity {
+    fn name(&self) -> &'static str {
+        if self.odd { "odd" } else { "even" }
+    }
+
+    fn allowed(&self, board: &Board, index: usize) -> Candidates {
+        let all = solve::all(board.width());
+        if !self.cells.contains(&index) {
+            return all;
+        }
+        // bit 0 is 1, so odd numbers are the even bits
+        all & if self.odd { 0x5555_5555 } else { 0xaaaa_aaaa }
+    }
+
+    fn marks(&self, _board: &Board) -> Vec<Mark> {
+        let mark = if self.odd { Mark::Circle } else { Mark::Square };
+        self.cells.iter().copied().map(mark).collect()
+    }
+
+    fn moved(&self, dests: &[usize]) -> Option<Arc<dyn Constraint>> {
+        let mut cells: Vec<usize> = self.cells.iter().map(|&i| dests[i]).collect();
+        cells.sort();
+        Some(Arc::new(Parity {
+            odd: self.odd,
+            cells,
+        }))
+    }
+}
+
+/// Not a rule: cells the setter colored in, kept so they're drawn the same
+#[derive(Debug)]
+pub struct Colored {
+    pub rgb: [u8; 3],
+    pub cells: Vec<usize>,
+}
+
+impl Colored {
+    /// `rrggbb:cell,cell,...` with the color in hex, on a board of `size` cells
+    fn parse(s: &str, size: usize) -> Option<Colored> {
+        let (hex, cells) = s.split_once(':')?;
+        let rgb = u32::from_str_radix(hex, 16)
+            .ok()
+            .filter(|_| hex.len() == 6)?;
+        let cells = cells
+            .split(',')
+            .map(|cell| cell.parse().ok().filter(|&i| i < size))
+            .collect::<Option<Vec<usize>>>()?;
+        Some(Colored {
+            rgb: [(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8],
+            cells,
+        })
+    }
+}
+
+impl Constraint for Colored {
+    fn name(&self) -> &'static str {
+        "color"
+    }
+
+    fn marks(&self, _board: &Board) -> Vec<Mark> {
+        self.cells
+            .iter()
+            .map(|&i| Mark::Color(i, self.rgb))
+            .collect()
+    }
+
+    fn moved(&self, dests: &[usize]) -> Option<Arc<dyn Constraint>> {
+        let mut cells: Vec<usize> = self.cells.iter().map(|&i| dests[i]).collect();
+        cells.sort();
+        Some(Arc::new(Colored {
+            rgb: self.rgb,
+            cells,
+        }))
+    }
+
+    fn ignores_numbers(&self) -> bool {
+        true
+    }
+}
+
+impl Display for AntiKnight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Display for AntiKing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Display for NonConsecutive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Display for Windoku {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Display for Parity {
+    /// `even:cell,cell,...` or `odd:cell,cell,...`, like a cage
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_cells(f, self.name(), &self.cells)
+    }
+}
+
+impl Display for Colored {
+    /// `color:rrggbb:cell,cell,...`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [r, g, b] = self.rgb;
+        write_cells(f, &format!("color:{r:02x}{g:02x}{b:02x}"), &self.cells)
+    }
+}
+
+impl Board {
+    /// every number `constraints` allow in cell `index`
+    pub fn allowed(&self, index: usize) -> Candidates {
+        self.constraints
+            .iter()
+            .fold(solve::all(self.width()), |allowed, c| {
+                allowed & c.allowed(self, index)
+            })
+    }
+
+    /// cells that can't hold the same number as `index` because of `constraints`
+    pub fn constraint_peers(&self, index: usize) -> Vec<usize> {
+        self.constraints
+            .iter()
+            .flat_map(|c| c.peers(self, index))
+            .collect()
+    }
+
+    /// The cell that keeps `num` out of `index` because of `constraints`:
+    /// a unit or peer that has it, or `index` itself when it isn't allowed there
+    pub fn constraint_conflict(&self, index: usize, num: u8) -> Option<usize> {
+        let units = self.constraints.iter().flat_map(|c| c.units(self));
+        let seen = units
+            .filter(|unit| unit.contains(&index))
+            .flatten()
+            .chain(self.constraint_peers(index))
+            .find(|&i| i != index && self.cells[i] == Some(num));
+
+        seen.or((self.allowed(index) & 1 << (num - 1) == 0).then_some(index))
+    }
+}
@@ -0,0 +1,128 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    board::Board,
+    generate::{Strategy, generate_board},
+    seed::Seed,
+    solve::Difficulty,
+};
+
+/// A day in the proleptic Gregorian calendar
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    /// 1..=12
+    pub month: u32,
+    /// 1..=31
+    pub day: u32,
+}
+
+impl Date {
+    pub const fn new(year: i32, month: u32, day: u32) -> Self {
+        Self { year, month, day }
+    }
+
+    /// days since 1970-01-01, from Howard Hinnant's `days_from_civil`
+    pub fn days(self) -> i64 {
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = (self.month as i64 + 9) % 12;
+        let day_of_year = (153 * month + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146097 + day_of_era - 719468
+    }
+
+    /// inverse of `days`
+    pub fn from_days(days: i64) -> Self {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month + 2) / 5 + 1;
+        let month = if month < 10 { month + 3 } else { month - 9 };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+        Self::new(year as i32, month as u32, day as u32)
+    }
+
+    /// 0 for monday up to 6 for sunday
+    pub fn weekday(self) -> u32 {
+        // 1970-01-01 was a thursday
+        (self.days() + 3).rem_euclid(7) as u32
+    }
+
+    pub fn add_days(self, days: i64) -> Self {
+        Self::from_days(self.days() + days)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// `YYYY-MM-DD`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date {s:?}, expected YYYY-MM-DD");
+
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+
+        let date = Self::new(
+            year.parse().map_err(|_| invalid())?,
+            month.parse().map_err(|_| invalid())?,
+            day.parse().map_err(|_| invalid())?,
+        );
+        // out of range days wouldn't survive a round trip
+        if Self::from_days(date.days()) != date {
+            return Err(invalid());
+        }
+        Ok(date)
+    }
+}
+
+/// The puzzle of the day, the same for everyone with the same date
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Daily {
+    pub date: Date,
+    pub seed: Seed,
+    pub difficulty: Difficulty,
+}
+
+impl Daily {
+    pub fn new(date: Date) -> Self {
+        // splitmix64, so neighbouring days get unrelated seeds
+        let mut seed = (date.days() as u64).wrapping_add(0x9e3779b97f4a7c15);
+        seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d049bb133111eb);
+        seed ^= seed >> 31;
+
+        // easy at the start of the week, expert on sunday
+        let difficulty = match date.weekday() {
+            0 | 1 => Difficulty::Easy,
+            2 | 3 => Difficulty::Medium,
+            4 | 5 => Difficulty::Hard,
+            _ => Difficulty::Expert,
+        };
+
+        Self {
+            date,
+            seed: Seed(seed),
+            difficulty,
+        }
+    }
+
+    pub fn board(&self) -> Board {
+        generate_board(Strategy::Unique(self.difficulty), self.seed)
+    }
+}
@@ -0,0 +1,258 @@
+use std::str::FromStr;
+
+use crate::board::{Board, Cage, ParseError};
+use crate::constraint;
+use crate::fpuzzles;
+
+/// Text formats puzzles are stored in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// one puzzle per line, `.` or `0` for empty cells, optional comment after the grid,
+    /// regions and cages of variants at the end of the comment
+    Line,
+    /// SadMan Sudoku: one row per line, `#` comments, puzzles separated by blank lines
+    Sdk,
+    /// rows with segment separators, the same as `Board`'s `Display`
+    Grid,
+    /// f-puzzles JSON, or a SudokuPad link per line, see `fpuzzles`
+    FPuzzles,
+}
+// Sdk and Grid only have room for the numbers, so they leave out regions and cages
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "line" => Ok(Self::Line),
+            "sdk" => Ok(Self::Sdk),
+            "grid" => Ok(Self::Grid),
+            "fpuzzles" => Ok(Self::FPuzzles),
+            _ => Err(format!("unknown format {s:?}")),
+        }
+    }
+}
+
+impl Format {
+    /// Every puzzle in `text`, or the first error. Use `parse` to keep the valid
+    /// puzzle lines of a text with some invalid ones.
+    pub fn read(self, text: &str) -> Result<Vec<Board>, ParseError> {
+        match self {
+            Self::Line => {
+                let (puzzles, errors) = parse(text);
+                if let Some(&(_, e)) = errors.first() {
+                    return Err(e);
+                }
+                Ok(puzzles.into_iter().map(|puzzle| puzzle.board).collect())
+            }
+            Self::Sdk | Self::Grid => read_blocks(text),
+            Self::FPuzzles => {
+                let puzzles = fpuzzles::read(text)?;
+                Ok(puzzles.into_iter().map(|puzzle| puzzle.board).collect())
+            }
+        }
+    }
+
+    pub fn write(self, board: &Board) -> String {
+        match self {
+            Self::Line => board.line() + &rules_comment(board) + "\n",
+            Self::Sdk => {
+                let line = board.line();
+                let mut text = String::new();
+                for row in line.as_bytes().chunks(board.width()) {
+                    text += std::str::from_utf8(row).unwrap();
+                    text += "\n";
+                }
+                text
+            }
+            Self::Grid => board.to_string(),
+            Self::FPuzzles => fpuzzles::link(board) + "\n",
+        }
+    }
+
+    /// what goes between two written puzzles
+    pub fn separator(self) -> &'static str {
+        match self {
+            Self::Line | Self::FPuzzles => "",
+            Self::Sdk | Self::Grid => "\n",
+        }
+    }
+}
+
+/// A single puzzle from a file of puzzle lines
+#[derive(Clone, Debug)]
+pub struct Puzzle {
+    pub board: Board,
+    /// whatever followed the grid on its line, usually a rating
+    pub comment: String,
+}
+
+/// words right after a puzzle line's grid that start its rules
+const RULES: [&str; 4] = ["diagonal", "regions", "cages", "constraints"];
+
+/// The rules that don't fit in the grid, to put right after it on a puzzle line:
+/// ` diagonal` for `Board::diagonals`,
+/// ` regions 111222...` for jigsaw regions, see `Board::regions_line`,
+/// ` cages 10:0,1,9 7:2,3 ...` for Killer cages and
+/// ` constraints anti-knight odd:0,4 ...` for `Board::constraints`.
+/// Nothing for other puzzles.
+pub fn rules_comment(board: &Board) -> String {
+    let mut text = String::new();
+    if board.diagonals {
+        text += " diagonal";
+    }
+    if let Some(regions) = board.regions_line() {
+        text += &format!(" regions {regions}");
+    }
+    if !board.cages.is_empty() {
+        text += " cages";
+        for cage in &board.cages {
+            text += &format!(" {cage}");
+        }
+    }
+    if !board.constraints.is_empty() {
+        text += " constraints";
+        for constraint in &board.constraints {
+            text += &format!(" {constraint}");
+        }
+    }
+    text
+}
+
+/// Read a puzzle line: the grid, optionally followed by whitespace, the rules written
+/// by `rules_comment` and a comment. The rules end at the first word that can't belong
+/// to them, so rule words further on are part of the comment.
+pub fn parse_line(line: &str) -> Result<Puzzle, ParseError> {
+    let line = line.trim();
+    let (grid, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+    let (mut end, mut rule, mut previous) = (0, "", "");
+    for word in rest.split(' ') {
+        let belongs = match rule {
+            _ if word.is_empty() || RULES.contains(&word) => true,
+            // a single word of regions
+            "regions" => previous == "regions",
+            "cages" => word.contains(':'),
+            // those without cells don't depend on the size
+            "constraints" => word.contains(':') || constraint::parse(word, 0).is_some(),
+            _ => false,
+        };
+        if !belongs {
+            break;
+        }
+        if RULES.contains(&word) {
+            rule = word;
+        }
+        if !word.is_empty() {
+            previous = word;
+        }
+        end = (end + word.len() + 1).min(rest.len());
+    }
+    let (rules, comment) = rest.split_at(end);
+
+    Ok(Puzzle {
+        board: Board::from_line(grid, |board| read_rules(board, rules))?,
+        comment: comment.trim().to_owned(),
+    })
+}
+
+/// add the rules written by `rules_comment` to an empty board
+pub(crate) fn read_rules(board: &mut Board, rules: &str) -> Result<(), ParseError> {
+    let mut rule = "";
+    for word in rules.split_whitespace() {
+        if RULES.contains(&word) {
+            rule = word;
+            board.diagonals |= word == "diagonal";
+            continue;
+        }
+        match rule {
+            // it's on or off, nothing follows it
+            "diagonal" => return Err(ParseError::InvalidRule),
+            "regions" => {
+                let regions = board.parse_regions(word);
+                board.regions = Some(regions.ok_or(ParseError::InvalidRegions)?);
+            }
+            "constraints" => {
+                let constraint = constraint::parse(word, board.size());
+                board
+                    .constraints
+                    .push(constraint.ok_or(ParseError::InvalidConstraint)?);
+            }
+            _ => {
+                let cage: Cage = word.parse().map_err(|_| ParseError::InvalidCage)?;
+                let taken = |i: &usize| *i >= board.size() || board.cage(*i).is_some();
+                if cage.cells.iter().any(taken) {
+                    return Err(ParseError::InvalidCage);
+                }
+                board.cages.push(cage);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read puzzles from text, one per line.
+/// Blank lines and lines starting with `#` are skipped. So are lines that don't
+/// hold a valid puzzle, their errors come second, with line numbers from 1.
+pub fn parse(text: &str) -> (Vec<Puzzle>, Vec<(usize, ParseError)>) {
+    let mut puzzles = Vec::new();
+    let mut errors = Vec::new();
+    let lines = text.lines().enumerate();
+    for (n, line) in lines.filter(|(_, line)| !skipped(line)) {
+        match parse_line(line) {
+            Ok(puzzle) => puzzles.push(puzzle),
+            Err(e) => errors.push((n + 1, e)),
+        }
+    }
+    (puzzles, errors)
+}
+
+/// `text` with the line of its first puzzle, the one `parse` reads first, swapped
+/// for `line`. Every other line stays. Text without a puzzle gets `line` at the end.
+pub fn replace_first(text: &str, line: &str) -> String {
+    let line = line.trim_end();
+    let mut lines: Vec<&str> = text.lines().collect();
+    let first = lines
+        .iter()
+        .position(|l| !skipped(l) && parse_line(l).is_ok());
+    match first {
+        Some(n) => lines[n] = line,
+        None => lines.push(line),
+    }
+    lines.join("\n") + "\n"
+}
+
+/// blank lines and comments
+fn skipped(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+/// Read grids written over several lines. Only digits, letters and `.` count as cells,
+/// so separators like `|` and `-+-` lines are ignored.
+/// A grid ends after a blank line or as many rows as its first row has cells.
+fn read_blocks(text: &str) -> Result<Vec<Board>, ParseError> {
+    let mut boards = Vec::new();
+    let mut rows: Vec<String> = Vec::new();
+
+    for line in text.lines().chain([""]) {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let row: String = line
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '.')
+            .collect();
+        if !row.is_empty() {
+            rows.push(row);
+        }
+
+        if !rows.is_empty() && (rows.len() == rows[0].len() || line.is_empty()) {
+            boards.push(rows.concat().parse()?);
+            rows.clear();
+        }
+    }
+    Ok(boards)
+}
@@ -0,0 +1,202 @@
+use std::collections::{HashMap, HashSet};
+
+/// the characters of `compressToBase64`, `=` pads the end
+const BASE64: &[u8; 65] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+
+/// Bits written least significant first into characters of `BASE64`,
+/// most significant bit first within a character, and the dictionary
+/// and code width they depend on
+struct Writer {
+    out: String,
+    value: u8,
+    position: usize,
+    dictionary: HashMap<Vec<u16>, u32>,
+    /// single characters in the dictionary that haven't been written out yet
+    to_create: HashSet<u16>,
+    enlarge_in: u32,
+    num_bits: u32,
+}
+
+impl Writer {
+    fn bit(&mut self, bit: u32) {
+        self.value = self.value << 1 | bit as u8;
+        self.position += 1;
+        if self.position == 6 {
+            self.out.push(BASE64[self.value as usize] as char);
+            self.value = 0;
+            self.position = 0;
+        }
+    }
+
+    fn bits(&mut self, mut value: u32, count: u32) {
+        for _ in 0..count {
+            self.bit(value & 1);
+            value >>= 1;
+        }
+    }
+
+    /// one code less until the codes need another bit
+    fn count_down(&mut self) {
+        self.enlarge_in -= 1;
+        if self.enlarge_in == 0 {
+            self.enlarge_in = 1 << self.num_bits;
+            self.num_bits += 1;
+        }
+    }
+
+    /// `w` as a new character or as its dictionary entry
+    fn write(&mut self, w: &[u16]) {
+        if w.len() == 1 && self.to_create.remove(&w[0]) {
+            let c = w[0] as u32;
+            if c < 256 {
+                self.bits(0, self.num_bits);
+                self.bits(c, 8);
+            } else {
+                self.bits(1, self.num_bits);
+                self.bits(c, 16);
+            }
+            self.count_down();
+        } else {
+            self.bits(self.dictionary[w], self.num_bits);
+        }
+        self.count_down();
+    }
+}
+
+/// The same as lz-string's `compressToBase64`, which f-puzzles and SudokuPad
+/// use for puzzles in links. It works on UTF-16 like JavaScript strings do.
+pub fn compress(text: &str) -> String {
+    let mut out = Writer {
+        out: String::new(),
+        value: 0,
+        position: 0,
+        dictionary: HashMap::new(),
+        to_create: HashSet::new(),
+        enlarge_in: 2,
+        num_bits: 2,
+    };
+    // 0, 1 and 2 start new characters and the end
+    let mut dict_size = 3;
+    let mut w: Vec<u16> = Vec::new();
+
+    for c in text.encode_utf16() {
+        if !out.dictionary.contains_key(&[c][..]) {
+            out.dictionary.insert(vec![c], dict_size);
+            dict_size += 1;
+            out.to_create.insert(c);
+        }
+        let mut wc = w.clone();
+        wc.push(c);
+        if out.dictionary.contains_key(&wc) {
+            w = wc;
+        } else {
+            out.write(&w);
+            out.dictionary.insert(wc, dict_size);
+            dict_size += 1;
+            w = vec![c];
+        }
+    }
+    if !w.is_empty() {
+        out.write(&w);
+    }
+
+    // the end of the stream, then always at least one more character
+    out.bits(2, out.num_bits);
+    out.bit(0);
+    while out.position != 0 {
+        out.bit(0);
+    }
+    let mut text = out.out;
+    while !text.len().is_multiple_of(4) {
+        text.push('=');
+    }
+    text
+}
+
+/// Bits read the way `Writer` writes them
+struct Reader<'a> {
+    values: &'a [u32],
+    index: usize,
+    value: u32,
+    position: u32,
+}
+
+impl Reader<'_> {
+    fn bits(&mut self, count: u32) -> u32 {
+        let mut bits = 0;
+        for power in 0..count {
+            if self.value & self.position != 0 {
+                bits |= 1 << power;
+            }
+            self.position >>= 1;
+            if self.position == 0 {
+                self.position = 32;
+                self.value = self.values.get(self.index).copied().unwrap_or(0);
+                self.index += 1;
+            }
+        }
+        bits
+    }
+}
+
+/// The inverse of `compress`, `None` if `text` isn't compressed data
+pub fn decompress(text: &str) -> Option<String> {
+    let values = text
+        .trim()
+        .bytes()
+        .map(|b| BASE64.iter().position(|&c| c == b).map(|v| v as u32))
+        .collect::<Option<Vec<u32>>>()?;
+    let mut reader = Reader {
+        values: &values,
+        index: 1,
+        value: *values.first()?,
+        position: 32,
+    };
+
+    let mut dictionary: Vec<Vec<u16>> = vec![Vec::new(); 3];
+    let (mut enlarge_in, mut num_bits) = (4u32, 3u32);
+
+    let c = match reader.bits(2) {
+        0 => reader.bits(8),
+        1 => reader.bits(16),
+        _ => return Some(String::new()),
+    };
+    let mut w = vec![c as u16];
+    dictionary.push(w.clone());
+    let mut result = w.clone();
+
+    loop {
+        if reader.index > values.len() {
+            return None;
+        }
+        let mut c = reader.bits(num_bits) as usize;
+        match c {
+            0 | 1 => {
+                let bits = reader.bits(if c == 0 { 8 } else { 16 });
+                dictionary.push(vec![bits as u16]);
+                c = dictionary.len() - 1;
+                enlarge_in -= 1;
+            }
+            2 => return String::from_utf16(&result).ok(),
+            _ => (),
+        }
+        if enlarge_in == 0 {
+            enlarge_in = 1 << num_bits;
+            num_bits += 1;
+        }
+
+        let entry = match dictionary.get(c) {
+            Some(entry) if c >= 3 => entry.clone(),
+            _ if c == dictionary.len() => [w.as_slice(), &w[..1]].concat(),
+            _ => return None,
+        };
+        result.extend(&entry);
+        dictionary.push([w.as_slice(), &entry[..1]].concat());
+        enlarge_in -= 1;
+        w = entry;
+        if enlarge_in == 0 {
+            enlarge_in = 1 << num_bits;
+            num_bits += 1;
+        }
+    }
+}
@@ -0,0 +1,507 @@
+//! Puzzles shared from f-puzzles and SudokuPad: f-puzzles JSON, as it is or
+//! compressed into a link like `https://sudokupad.app/fpuzzles...`.
+//! Rules sudoru doesn't play are refused, cosmetic lines, shapes and text left out.
+
+mod json;
+mod lz;
+
+pub use json::Json;
+pub use lz::{compress, decompress};
+
+use crate::board::{Board, Cage, ParseError, Shape, symbol};
+use crate::constraint::{Constraint, Windoku};
+use crate::format::{self, Puzzle};
+
+/// SudokuPad opens the compressed f-puzzles data after this
+pub const SUDOKUPAD: &str = "https://sudokupad.app/fpuzzles";
+
+/// f-puzzles rules sudoru doesn't play, to name them when refusing a puzzle
+const UNSUPPORTED: [&str; 11] = [
+    "disjointgroups",
+    "littlekillersum",
+    "minimum",
+    "maximum",
+    "palindrome",
+    "renban",
+    "whispers",
+    "regionsumline",
+    "betweenline",
+    "quadruple",
+    "clone",
+];
+
+/// drawings that don't change the rules
+const COSMETIC: [&str; 5] = ["line", "rectangle", "circle", "text", "cage"];
+
+fn invalid(what: &'static str) -> ParseError {
+    ParseError::InvalidFPuzzles(what)
+}
+
+/// whether `text` is f-puzzles data or links rather than puzzle lines
+pub fn is_fpuzzles(text: &str) -> bool {
+    let text = text.trim_start();
+    let first = text.lines().find(|line| !line.trim().starts_with('#'));
+    text.starts_with('{')
+        || first.is_some_and(|line| {
+            line.contains("fpuzzles") || line.contains("?load=") || line.starts_with("N4Ig")
+        })
+}
+
+/// Read a JSON puzzle, or one link or compressed puzzle per line.
+/// Blank lines and lines starting with `#` are skipped.
+pub fn read(text: &str) -> Result<Vec<Puzzle>, ParseError> {
+    let text = text.trim();
+    if text.starts_with('{') {
+        let json = Json::parse(text).ok_or(invalid("not JSON"))?;
+        return Ok(vec![from_json(&json)?]);
+    }
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| from_json(&unpack(line)?))
+        .collect()
+}
+
+/// the JSON in a SudokuPad or f-puzzles link, or in the compressed data on its own
+fn unpack(link: &str) -> Result<Json, ParseError> {
+    let data = match (link.rsplit_once("?load="), link.rsplit_once("fpuzzles")) {
+        (Some((_, data)), _) | (None, Some((_, data))) => data,
+        (None, None) => link.rsplit('/').next().unwrap_or(link),
+    };
+    let data = percent_decoded(data.split(['?', '&', '#']).next().unwrap_or(data));
+    if data.starts_with("scl") || data.starts_with("ctc") {
+        return Err(ParseError::Unsupported("SudokuPad's own puzzle format"));
+    }
+    let json = lz::decompress(&data).ok_or(invalid("not a compressed puzzle"))?;
+    Json::parse(&json).ok_or(invalid("not JSON"))
+}
+
+/// `%2B` and the like in links back to the characters they stand for
+fn percent_decoded(s: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut i = 0;
+    while i < s.len() {
+        let escaped = s.as_bytes()[i] == b'%';
+        match s.get(i + 1..i + 3).map(|hex| u8::from_str_radix(hex, 16)) {
+            Some(Ok(byte)) if escaped => {
+                bytes.push(byte);
+                i += 3;
+            }
+            _ => {
+                bytes.push(s.as_bytes()[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// the cell at `R1C1` for the top-left cell, as row and column counted from 1
+fn row_col(json: &Json) -> Option<(usize, usize)> {
+    let rc = json.as_str()?.to_ascii_uppercase();
+    let (r, c) = rc.strip_prefix('R')?.split_once('C')?;
+    Some((r.parse().ok()?, c.parse().ok()?))
+}
+
+/// the index of a cell written as `R1C1` on a board `side` cells wide
+fn cell(json: &Json, side: usize) -> Result<usize, ParseError> {
+    let on_board = |&(r, c): &(usize, usize)| (1..=side).contains(&r) && (1..=side).contains(&c);
+    let (r, c) = row_col(json).filter(on_board).ok_or(invalid("cell"))?;
+    Ok((r - 1) * side + c - 1)
+}
+
+/// the indices of an array of cells
+fn cells(json: Option<&Json>, side: usize) -> Result<Vec<usize>, ParseError> {
+    items(json)?.iter().map(|c| cell(c, side)).collect()
+}
+
+fn items(json: Option<&Json>) -> Result<&[Json], ParseError> {
+    json.and_then(Json::as_array)
+        .ok_or(invalid("expected an array"))
+}
+
+/// `name:cell,cell,...`, the way constraints on cells are written
+fn cells_word(name: &str, cells: &[usize]) -> String {
+    let cells: Vec<String> = cells.iter().map(usize::to_string).collect();
+    format!("{name}:{}", cells.join(","))
+}
+
+/// `#A8A8A8` as `a8a8a8`, `None` for colors that aren't written that way
+fn hex(color: &str) -> Option<String> {
+    let hex = color.strip_prefix('#')?;
+    (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| hex.to_ascii_lowercase())
+}
+
+/// Read a puzzle from f-puzzles JSON, with its title as the comment.
+/// Only givens are read, not numbers a solver filled in.
+pub fn from_json(json: &Json) -> Result<Puzzle, ParseError> {
+    let side = json
+        .get("size")
+        .and_then(Json::as_u32)
+        .ok_or(invalid("no size"))? as usize;
+    let shape = Shape::from_side(side).ok_or(ParseError::Length(side * side))?;
+    let rows = items(json.get("grid"))?;
+    if rows.len() != side {
+        return Err(invalid("grid"));
+    }
+
+    let mut grid = String::new();
+    let mut regions = Vec::new();
+    // colors in the order they first appear, with their cells
+    let mut colors: Vec<(String, Vec<usize>)> = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+        let row = items(Some(row))?;
+        if row.len() != side {
+            return Err(invalid("grid"));
+        }
+        for (x, cell) in row.iter().enumerate() {
+            let given = cell.get("given").is_some_and(Json::is_true);
+            match cell.get("value") {
+                Some(value) if given => {
+                    let num = value.as_u32().filter(|num| (1..=side as u32).contains(num));
+                    grid.push(symbol(num.ok_or(invalid("given"))? as u8));
+                }
+                _ => grid.push('.'),
+            }
+
+            regions.push(match cell.get("region") {
+                Some(region) => region.as_u32().ok_or(invalid("region"))? as usize,
+                None => shape.seg_index((x, y)),
+            });
+
+            let array = cell.get("cArray").and_then(Json::as_array);
+            let color = cell.get("c").or(array.and_then(<[Json]>::first));
+            if let Some(rgb) = color.and_then(Json::as_str).and_then(hex) {
+                let index = y * side + x;
+                match colors.iter_mut().find(|(c, _)| *c == rgb) {
+                    Some((_, cells)) => cells.push(index),
+                    None => colors.push((rgb, vec![index])),
+                }
+            }
+        }
+    }
+
+    let mut rules = String::new();
+    let default: Vec<usize> = (0..side * side).map(|i| shape.seg_index(i)).collect();
+    if regions != default {
+        if regions.iter().any(|&r| r >= side) {
+            return Err(ParseError::InvalidRegions);
+        }
+        rules += " regions ";
+        rules.extend(regions.iter().map(|&r| symbol(r as u8 + 1)));
+    }
+
+    let mut cages = Vec::new();
+    let mut constraints = Vec::new();
+    let mut diagonals = [false; 2];
+    let Json::Object(fields) = json else {
+        return Err(invalid("not an object"));
+    };
+    for (key, value) in fields {
+        match key.as_str() {
+            "size" | "grid" | "title" | "author" | "ruleset" | "solution" => (),
+            key if COSMETIC.contains(&key) => (),
+            "diagonal+" => diagonals[0] = value.is_true(),
+            "diagonal-" => diagonals[1] = value.is_true(),
+            "antiknight" if value.is_true() => constraints.push("anti-knight".to_owned()),
+            "antiking" if value.is_true() => constraints.push("anti-king".to_owned()),
+            "nonconsecutive" if value.is_true() => constraints.push("non-consecutive".to_owned()),
+            "killercage" => {
+                for cage in items(Some(value))? {
+                    let sum = cage.get("value").and_then(Json::as_u32);
+                    let sum = sum.ok_or(ParseError::Unsupported("killer cage without a sum"))?;
+                    let cells = cells(cage.get("cells"), side)?;
+                    cages.push(Cage::new(cells, sum).to_string());
+                }
+            }
+            "thermometer" => {
+                for thermo in items(Some(value))? {
+                    for line in items(thermo.get("lines"))? {
+                        constraints.push(cells_word("thermo", &cells(Some(line), side)?));
+                    }
+                }
+            }
+            "arrow" => {
+                for arrow in items(Some(value))? {
+                    let bulb = cells(arrow.get("cells"), side)?;
+                    let lines = items(arrow.get("lines"))?;
+                    let ([bulb], [line]) = (bulb.as_slice(), lines) else {
+                        return Err(ParseError::Unsupported("arrow with several cells or lines"));
+                    };
+                    let mut cells = vec![*bulb];
+                    cells.extend(
+                        self::cells(Some(line), side)?
+                            .into_iter()
+                            .filter(|i| i != bulb),
+                    );
+                    constraints.push(cells_word("arrow", &cells));
+                }
+            }
+            "difference" | "ratio" | "xv" => {
+                for edge in items(Some(value))? {
+                    let value = edge.get("value");
+                    let number = value.and_then(Json::as_u32);
+                    let word = match (key.as_str(), value.and_then(Json::as_str)) {
+                        ("difference", _) if value.is_none() || number == Some(1) => "white",
+                        ("ratio", _) if value.is_none() || number == Some(2) => "black",
+                        ("xv", Some("X" | "x")) => "x",
+                        ("xv", Some("V" | "v")) => "v",
+                        ("difference", _) => {
+                            return Err(ParseError::Unsupported("difference other than 1"));
+                        }
+                        ("ratio", _) => return Err(ParseError::Unsupported("ratio other than 2")),
+                        _ => return Err(invalid("XV")),
+                    };
+                    constraints.push(cells_word(word, &cells(edge.get("cells"), side)?));
+                }
+            }
+            "negative" => {
+                let kinds: Vec<&str> = items(Some(value))?
+                    .iter()
+                    .filter_map(Json::as_str)
+                    .collect();
+                let kropki = ["ratio", "difference"].map(|kind| kinds.contains(&kind));
+                match kropki {
+                    [true, true] => constraints.push("kropki-negative".to_owned()),
+                    [false, false] => (),
+                    _ => return Err(ParseError::Unsupported("negative for one kind of dot")),
+                }
+                if kinds.contains(&"xv") {
+                    constraints.push("xv-negative".to_owned());
+                }
+            }
+            "odd" | "even" => {
+                let cells = items(Some(value))?
+                    .iter()
+                    .map(|c| cell(c.get("cell").unwrap_or(&Json::Null), side))
+                    .collect::<Result<Vec<usize>, _>>()?;
+                if !cells.is_empty() {
+                    constraints.push(cells_word(key, &cells));
+                }
+            }
+            "sandwichsum" => {
+                for sandwich in items(Some(value))? {
+                    let clue = sandwich.get("cell").and_then(row_col);
+                    let sum = sandwich.get("value").and_then(Json::as_u32);
+                    let line = match (clue, sum) {
+                        (Some((0, c)), Some(_)) if c > 0 => format!("c{}", c - 1),
+                        (Some((r, 0)), Some(_)) if r > 0 => format!("r{}", r - 1),
+                        _ => return Err(invalid("sandwich sum")),
+                    };
+                    constraints.push(format!("sandwich:{line}:{}", sum.unwrap_or(0)));
+                }
+            }
+            "extraregion" => {
+                let mut extra = items(Some(value))?
+                    .iter()
+                    .map(|region| {
+                        let mut cells = cells(region.get("cells"), side)?;
+                        cells.sort();
+                        Ok(cells)
+                    })
+                    .collect::<Result<Vec<Vec<usize>>, ParseError>>()?;
+                extra.sort();
+                let mut windoku = Windoku.units(&Board::with_shape(shape));
+                windoku.iter_mut().for_each(|unit| unit.sort());
+                windoku.sort();
+                if extra != windoku {
+                    return Err(ParseError::Unsupported("extra regions other than windoku"));
+                }
+                constraints.push("windoku".to_owned());
+            }
+            // rules that are off, and what isn't a rule at all
+            _ if matches!(
+                value,
+                Json::Bool(false) | Json::Null | Json::String(_) | Json::Number(_)
+            ) || value.as_array().is_some_and(<[Json]>::is_empty) => {}
+            key => {
+                let rule = UNSUPPORTED.iter().find(|&&rule| rule == key);
+                return Err(ParseError::Unsupported(rule.unwrap_or(&"an unknown rule")));
+            }
+        }
+    }
+    for (rgb, cells) in &colors {
+        constraints.push(cells_word(&format!("color:{rgb}"), cells));
+    }
+
+    if !cages.is_empty() {
+        rules += " cages ";
+        rules += &cages.join(" ");
+    }
+    if !constraints.is_empty() {
+        rules += " constraints ";
+        rules += &constraints.join(" ");
+    }
+    let diagonals = match diagonals {
+        [true, true] => true,
+        [false, false] => false,
+        _ => return Err(ParseError::Unsupported("a single diagonal")),
+    };
+
+    let board = Board::from_line(&grid, |board| {
+        board.diagonals = diagonals;
+        format::read_rules(board, &rules)
+    })?;
+    let title = json.get("title").and_then(Json::as_str).unwrap_or_default();
+    Ok(Puzzle {
+        board,
+        comment: title.to_owned(),
+    })
+}
+
+/// `R1C1` for the top-left cell
+fn rc(index: usize, side: usize) -> Json {
+    Json::String(format!("R{}C{}", index / side + 1, index % side + 1))
+}
+
+fn rcs(cells: &[usize], side: usize) -> Json {
+    Json::Array(cells.iter().map(|&i| rc(i, side)).collect())
+}
+
+fn object(fields: &[(&str, Json)]) -> Json {
+    Json::Object(
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect(),
+    )
+}
+
+/// The constraints of `board` that `to_json` leaves out, as the puzzle line has them.
+/// f-puzzles has no greater-than signs, so that's the comparisons.
+pub fn left_out(board: &Board) -> Vec<String> {
+    board
+        .constraints
+        .iter()
+        .filter(|c| c.name() == "greater-than")
+        .map(|c| c.to_string())
+        .collect()
+}
+
+/// The f-puzzles JSON of `board`'s givens and rules, except for those in `left_out`.
+pub fn to_json(board: &Board) -> Json {
+    let side = board.width();
+    let mut cells: Vec<Vec<(String, Json)>> = vec![Vec::new(); board.size()];
+    for (i, cell) in cells.iter_mut().enumerate() {
+        if let Some(num) = board.cells[i]
+            && board.givens[i]
+        {
+            cell.push(("value".to_owned(), Json::Number(num as f64)));
+            cell.push(("given".to_owned(), Json::Bool(true)));
+        }
+        if let Some(regions) = &board.regions {
+            cell.push(("region".to_owned(), Json::Number(regions[i] as f64)));
+        }
+    }
+
+    // rules in the order they're first seen, each with its items
+    let mut rules: Vec<(String, Json)> = Vec::new();
+    let mut add = |key: &str, item: Json| match rules.iter_mut().find(|(k, _)| k == key) {
+        Some((_, Json::Array(items))) => items.push(item),
+        _ => rules.push((key.to_owned(), Json::Array(vec![item]))),
+    };
+    let mut flags: Vec<(String, Json)> = Vec::new();
+    if board.diagonals {
+        flags.push(("diagonal+".to_owned(), Json::Bool(true)));
+        flags.push(("diagonal-".to_owned(), Json::Bool(true)));
+    }
+    for cage in &board.cages {
+        let value = Json::String(cage.sum.to_string());
+        add(
+            "killercage",
+            object(&[("cells", rcs(&cage.cells, side)), ("value", value)]),
+        );
+    }
+
+    let mut negative = Vec::new();
+    for constraint in &board.constraints {
+        // written the way the puzzle line has them, so they're read back from that
+        let word = constraint.to_string();
+        let (name, rest) = word.split_once(':').unwrap_or((&word, ""));
+        let list =
+            |s: &str| -> Vec<usize> { s.split(',').filter_map(|i| i.parse().ok()).collect() };
+        match name {
+            "anti-knight" | "anti-king" | "non-consecutive" => {
+                flags.push((name.replace('-', ""), Json::Bool(true)));
+            }
+            "windoku" => {
+                for unit in constraint.units(board) {
+                    add("extraregion", object(&[("cells", rcs(&unit, side))]));
+                }
+            }
+            "kropki-negative" => negative.extend(["ratio", "difference"]),
+            "xv-negative" => negative.push("xv"),
+            "thermo" => add(
+                "thermometer",
+                object(&[("lines", Json::Array(vec![rcs(&list(rest), side)]))]),
+            ),
+            "arrow" => {
+                let cells = list(rest);
+                let lines = Json::Array(vec![rcs(&cells, side)]);
+                add(
+                    "arrow",
+                    object(&[("cells", rcs(&cells[..1], side)), ("lines", lines)]),
+                );
+            }
+            "white" => add("difference", object(&[("cells", rcs(&list(rest), side))])),
+            "black" => add("ratio", object(&[("cells", rcs(&list(rest), side))])),
+            "x" | "v" => {
+                let value = Json::String(name.to_ascii_uppercase());
+                add(
+                    "xv",
+                    object(&[("cells", rcs(&list(rest), side)), ("value", value)]),
+                );
+            }
+            "odd" | "even" => {
+                for i in list(rest) {
+                    add(name, object(&[("cell", rc(i, side))]));
+                }
+            }
+            "sandwich" => {
+                let (line, sum) = rest.split_once(':').unwrap_or_default();
+                let n: usize = line[1..].parse().unwrap_or_default();
+                let clue = match &line[..1] {
+                    "c" => format!("R0C{}", n + 1),
+                    _ => format!("R{}C0", n + 1),
+                };
+                let value = Json::String(sum.to_owned());
+                add(
+                    "sandwichsum",
+                    object(&[("cell", Json::String(clue)), ("value", value)]),
+                );
+            }
+            "color" => {
+                let (rgb, rest) = rest.split_once(':').unwrap_or_default();
+                for i in list(rest) {
+                    let color = Json::String(format!("#{}", rgb.to_ascii_uppercase()));
+                    cells[i].push(("c".to_owned(), color));
+                }
+            }
+            // comparisons
+            _ => (),
+        }
+    }
+    if !negative.is_empty() {
+        let kinds = negative
+            .into_iter()
+            .map(|kind| Json::String(kind.to_owned()));
+        flags.push(("negative".to_owned(), Json::Array(kinds.collect())));
+    }
+
+    let grid = cells
+        .chunks(side)
+        .map(|row| Json::Array(row.iter().cloned().map(Json::Object).collect()))
+        .collect();
+    let mut fields = vec![
+        ("size".to_owned(), Json::Number(side as f64)),
+        ("grid".to_owned(), Json::Array(grid)),
+    ];
+    fields.extend(flags);
+    fields.extend(rules);
+    Json::Object(fields)
+}
+
+/// a SudokuPad link that opens `board`, see `to_json`
+pub fn link(board: &Board) -> String {
+    format!("{SUDOKUPAD}{}", lz::compress(&to_json(board).to_string()))
+}
@@ -0,0 +1,424 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::{Rng, seq::SliceRandom};
+
+use crate::board::{Board, Cell, ParseError, PlaceError, Position, from_symbol, symbol};
+use crate::seed::Seed;
+use crate::solve::{self, Candidates};
+
+/// cells across one grid, every grid is a classic 9×9 board
+const GRID: usize = 9;
+
+/// How the grids of a gattai puzzle overlap
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// two grids sharing the segment in a corner, also called Twodoku
+    Twin,
+    /// four grids in a square, next to each other ones share six rows or columns
+    Butterfly,
+    /// a grid in the middle sharing a corner segment with one grid in every corner
+    Samurai,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 3] = [Self::Twin, Self::Butterfly, Self::Samurai];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Twin => "twin",
+            Self::Butterfly => "butterfly",
+            Self::Samurai => "samurai",
+        }
+    }
+
+    /// the top-left cell of every grid, as (x, y)
+    pub fn grids(self) -> &'static [(usize, usize)] {
+        match self {
+            Self::Twin => &[(0, 0), (6, 6)],
+            Self::Butterfly => &[(0, 0), (3, 0), (0, 3), (3, 3)],
+            Self::Samurai => &[(0, 0), (12, 0), (6, 6), (0, 12), (12, 12)],
+        }
+    }
+
+    /// cells across the whole puzzle, which is as high as it's wide
+    pub fn side(self) -> usize {
+        self.grids().iter().map(|&(x, _)| x + GRID).max().unwrap()
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|layout| layout.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown layout {s:?}"))
+    }
+}
+
+/// Gattai or multi-grid Sudoku: classic 9×9 grids overlapping on a larger square,
+/// where the cells they share hold the same number in all of them.
+/// Cells outside every grid are never filled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gattai {
+    pub layout: Layout,
+    /// every cell of the square `layout.side()` cells wide
+    pub cells: Vec<Cell>,
+    /// cells that are part of the puzzle and can't be changed
+    pub givens: Vec<bool>,
+}
+
+impl Gattai {
+    pub fn new(layout: Layout) -> Self {
+        let size = layout.side() * layout.side();
+        Self {
+            layout,
+            cells: vec![None; size],
+            givens: vec![false; size],
+        }
+    }
+
+    pub fn side(&self) -> usize {
+        self.layout.side()
+    }
+    pub fn size(&self) -> usize {
+        self.side() * self.side()
+    }
+    pub fn index(&self, pos: impl Position) -> usize {
+        pos.index(self.side())
+    }
+    pub fn coords(&self, pos: impl Position) -> (usize, usize) {
+        pos.coords(self.side())
+    }
+
+    /// the grids `pos` is in, with its index on each of them
+    pub fn grids_at(&self, pos: impl Position) -> Vec<(usize, usize)> {
+        let (x, y) = self.coords(pos);
+        self.layout
+            .grids()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &(gx, gy))| (gx..gx + GRID).contains(&x) && (gy..gy + GRID).contains(&y))
+            .map(|(g, &(gx, gy))| (g, (x - gx, y - gy).index(GRID)))
+            .collect()
+    }
+
+    /// the cell is in at least one grid
+    pub fn in_play(&self, pos: impl Position) -> bool {
+        !self.grids_at(pos).is_empty()
+    }
+
+    /// the index of cell `index` of grid `g`
+    pub fn grid_cell(&self, g: usize, index: usize) -> usize {
+        let (gx, gy) = self.layout.grids()[g];
+        let (x, y) = index.coords(GRID);
+        self.index((gx + x, gy + y))
+    }
+
+    /// grid `g` on its own
+    pub fn grid(&self, g: usize) -> Board {
+        let mut board = Board::new();
+        for index in 0..board.size() {
+            let i = self.grid_cell(g, index);
+            board.cells[index] = self.cells[i];
+            board.givens[index] = self.givens[i];
+        }
+        board
+    }
+
+    /// Rows, columns and segments of every grid, the segments they share only once
+    pub fn units(&self) -> Vec<Vec<usize>> {
+        let mut units: Vec<Vec<usize>> = Vec::new();
+        for g in 0..self.layout.grids().len() {
+            for unit in Board::new().units() {
+                let unit: Vec<usize> = unit.iter().map(|&i| self.grid_cell(g, i)).collect();
+                if !units.contains(&unit) {
+                    units.push(unit);
+                }
+            }
+        }
+        units
+    }
+
+    /// Why `cell` can't go in `pos`, checked on every grid it's in.
+    /// Cells outside the grids count as givens.
+    pub fn placement_error(&self, pos: impl Position, cell: Cell) -> Option<PlaceError> {
+        let grids = self.grids_at(pos);
+        if grids.is_empty() {
+            return Some(PlaceError::Given);
+        }
+        grids
+            .into_iter()
+            .find_map(|(g, index)| self.grid(g).placement_error(index, cell))
+    }
+
+    pub fn place(&mut self, pos: impl Position, cell: Cell) -> Result<(), PlaceError> {
+        match self.placement_error(pos, cell) {
+            Some(e) => Err(e),
+            None => {
+                let index = self.index(pos);
+                self.cells[index] = cell;
+                Ok(())
+            }
+        }
+    }
+
+    /// mark every filled cell as a given
+    pub fn lock_givens(&mut self) {
+        for (given, cell) in self.givens.iter_mut().zip(&self.cells) {
+            *given = cell.is_some();
+        }
+    }
+
+    /// number of filled cells
+    pub fn clue_count(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.is_some()).count()
+    }
+
+    /// every cell of every grid is filled
+    pub fn is_solved(&self) -> bool {
+        (0..self.size()).all(|i| self.cells[i].is_some() || !self.in_play(i))
+    }
+
+    /// The cells in play on a single line, left to right and top to bottom,
+    /// skipping the ones outside the grids
+    pub fn line(&self) -> String {
+        (0..self.size())
+            .filter(|&i| self.in_play(i))
+            .map(|i| match self.cells[i] {
+                Some(num) => symbol(num),
+                None => '.',
+            })
+            .collect()
+    }
+
+    /// Read a line written by `line` for `layout`
+    pub fn from_line(layout: Layout, s: &str) -> Result<Gattai, ParseError> {
+        let mut gattai = Gattai::new(layout);
+        let cells: Vec<usize> = (0..gattai.size()).filter(|&i| gattai.in_play(i)).collect();
+        let s = s.trim();
+        let len = s.chars().count();
+        if len != cells.len() {
+            return Err(ParseError::Length(len));
+        }
+
+        for (&index, c) in cells.iter().zip(s.chars()) {
+            let cell = match c {
+                '.' | '0' => None,
+                c => match from_symbol(c) {
+                    Some(num) if num as usize <= GRID => Some(num),
+                    _ => return Err(ParseError::InvalidChar(c)),
+                },
+            };
+            if cell.is_some() {
+                gattai
+                    .place(index, cell)
+                    .map_err(|e| ParseError::Conflict(index, e))?;
+            }
+        }
+        gattai.lock_givens();
+
+        Ok(gattai)
+    }
+
+    /// any solution
+    pub fn solve(&self) -> Option<Gattai> {
+        let mut solution = None;
+        Solver::new(self)?.search(&mut solve::numbers, &mut |cells| {
+            solution = Some(cells.to_vec());
+            false
+        });
+        solution.map(|cells| Gattai {
+            cells,
+            ..self.clone()
+        })
+    }
+
+    /// there's exactly one solution
+    pub fn is_unique(&self) -> bool {
+        let Some(mut solver) = Solver::new(self) else {
+            return false;
+        };
+        let mut count = 0;
+        solver.search(&mut solve::numbers, &mut |_| {
+            count += 1;
+            count < 2
+        });
+        count == 1
+    }
+
+    /// A puzzle with exactly one solution, with as few givens as the random order
+    /// of removing them allows. The same layout and seed always give the same puzzle.
+    pub fn generate(layout: Layout, seed: Seed) -> Gattai {
+        let mut rng = seed.rng();
+        let empty = Gattai::new(layout);
+        let mut gattai = empty.random_solution(&mut rng);
+
+        let mut cells: Vec<usize> = (0..gattai.size()).filter(|&i| gattai.in_play(i)).collect();
+        cells.shuffle(&mut rng);
+        for index in cells {
+            let num = gattai.cells[index].take();
+            if !gattai.is_unique() {
+                gattai.cells[index] = num;
+            }
+        }
+        gattai.lock_givens();
+
+        gattai
+    }
+
+    fn random_solution(&self, rng: &mut impl Rng) -> Gattai {
+        let mut solution = None;
+        let mut solver = Solver::new(self).expect("an empty puzzle breaks no rules");
+        solver.search(
+            &mut |candidates| {
+                let mut nums = solve::numbers(candidates);
+                nums.shuffle(rng);
+                nums
+            },
+            &mut |cells| {
+                solution = Some(cells.to_vec());
+                false
+            },
+        );
+        Gattai {
+            cells: solution.expect("an empty puzzle has a solution"),
+            ..self.clone()
+        }
+    }
+}
+
+/// Backtracking over the units of all grids at once, picking the cell with the fewest
+/// candidates or a hidden single, like the solver in `solve` does for a single board
+struct Solver {
+    cells: Vec<Cell>,
+    /// the cells in play
+    open: Vec<usize>,
+    units: Vec<Vec<usize>>,
+    cell_units: Vec<Vec<usize>>,
+    /// numbers used in every unit
+    used: Vec<Candidates>,
+}
+
+impl Solver {
+    /// `None` if the givens already break a rule
+    fn new(gattai: &Gattai) -> Option<Self> {
+        let units = gattai.units();
+        let mut cell_units = vec![Vec::new(); gattai.size()];
+        for (u, unit) in units.iter().enumerate() {
+            for &index in unit {
+                cell_units[index].push(u);
+            }
+        }
+        let mut solver = Self {
+            cells: gattai.cells.clone(),
+            open: (0..gattai.size()).filter(|&i| gattai.in_play(i)).collect(),
+            used: vec![0; units.len()],
+            units,
+            cell_units,
+        };
+
+        for index in 0..gattai.size() {
+            if let Some(num) = gattai.cells[index] {
+                let bit = 1 << (num - 1);
+                if solver.candidates(index) & bit == 0 {
+                    return None;
+                }
+                solver.toggle(index, bit);
+            }
+        }
+        Some(solver)
+    }
+
+    fn candidates(&self, index: usize) -> Candidates {
+        let used = self.cell_units[index]
+            .iter()
+            .fold(0, |used, &u| used | self.used[u]);
+        solve::all(GRID) & !used
+    }
+
+    fn toggle(&mut self, index: usize, bit: Candidates) {
+        for &u in &self.cell_units[index] {
+            self.used[u] ^= bit;
+        }
+    }
+
+    fn next_choice(&self) -> Option<(usize, Candidates)> {
+        let mut best: Option<(usize, Candidates)> = None;
+        for &index in &self.open {
+            if self.cells[index].is_some() {
+                continue;
+            }
+            let candidates = self.candidates(index);
+            if best.is_none_or(|(_, c)| candidates.count_ones() < c.count_ones()) {
+                best = Some((index, candidates));
+                if candidates.count_ones() <= 1 {
+                    return best;
+                }
+            }
+        }
+        let best = best?;
+
+        for unit in &self.units {
+            let (mut once, mut twice, mut filled) = (0, 0, 0);
+            for &index in unit {
+                match self.cells[index] {
+                    Some(num) => filled |= 1 << (num - 1),
+                    None => {
+                        let candidates = self.candidates(index);
+                        twice |= once & candidates;
+                        once |= candidates;
+                    }
+                }
+            }
+            if once | filled != solve::all(GRID) {
+                return Some((best.0, 0));
+            }
+            let single = once & !twice;
+            if single != 0 {
+                let bit = single & single.wrapping_neg();
+                let index = unit
+                    .iter()
+                    .copied()
+                    .find(|&i| self.cells[i].is_none() && self.candidates(i) & bit != 0)
+                    .expect("a hidden single has a cell");
+                return Some((index, bit));
+            }
+        }
+        Some(best)
+    }
+
+    /// Calls `found` for every solution until it returns false
+    fn search(
+        &mut self,
+        order: &mut impl FnMut(Candidates) -> Vec<u8>,
+        found: &mut impl FnMut(&[Cell]) -> bool,
+    ) -> bool {
+        let Some((index, candidates)) = self.next_choice() else {
+            return found(&self.cells);
+        };
+
+        for num in order(candidates) {
+            let bit = 1 << (num - 1);
+            self.cells[index] = Some(num);
+            self.toggle(index, bit);
+
+            let go_on = self.search(order, found);
+
+            self.toggle(index, bit);
+            self.cells[index] = None;
+
+            if !go_on {
+                return false;
+            }
+        }
+        true
+    }
+}
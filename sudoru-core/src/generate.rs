@@ -0,0 +1,268 @@
+use rand::{Rng, seq::SliceRandom};
+
+use crate::board::Board;
+use crate::constraint::Edge;
+use crate::seed::Seed;
+use crate::solve::{self, Difficulty, Solutions};
+use crate::symmetry::Symmetry;
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    /// Try to insert and ignore if it fails
+    TryRandom,
+    /// Same as TryRandom, but it only inserts on random chance.
+    TryRandomSparse,
+    /// A puzzle with exactly one solution, rated at the given difficulty
+    Unique(Difficulty),
+    /// Same as Unique, with givens that follow the symmetry
+    Symmetric(Difficulty, Symmetry),
+    /// Same as Unique, but no given can be removed without losing uniqueness
+    Minimal(Difficulty),
+    /// Killer Sudoku: random cages over a random solution, then clues removed like Unique.
+    /// Without `givens` every clue is removed, and only the cages are left.
+    Killer {
+        difficulty: Difficulty,
+        givens: bool,
+    },
+    /// Jigsaw Sudoku: same as Unique, on random connected regions instead of segments
+    Jigsaw(Difficulty),
+    /// Greater-than Sudoku: same as Unique, with comparisons between the cells of every segment,
+    /// which usually leave few givens or none
+    Comparison(Difficulty),
+}
+
+/// how often to start over when a puzzle doesn't reach the difficulty
+const ATTEMPTS: usize = 200;
+
+/// most cells in a generated cage
+const MAX_CAGE: usize = 4;
+
+/// A classic 9×9 board, the same strategy and seed always give the same board
+pub fn generate_board(strategy: Strategy, seed: Seed) -> Board {
+    generate_from(&Board::new(), strategy, seed).expect("classic board has a solution")
+}
+
+/// A board with the shape and rules of `empty`, `None` if no board follows the rules.
+/// The same rules, strategy and seed always give the same board.
+pub fn generate_from(empty: &Board, strategy: Strategy, seed: Seed) -> Option<Board> {
+    let mut board = empty.empty();
+    let mut rng = seed.rng();
+    let max = board.width() as u8;
+
+    match strategy {
+        Strategy::TryRandom => {
+            for row in 0..board.height() {
+                for col in 0..board.width() {
+                    let num = rng.random_range(1..=max);
+                    let _ = board.place_at((col, row), Some(num));
+                }
+            }
+        }
+        Strategy::TryRandomSparse => {
+            for row in 0..board.height() {
+                for col in 0..board.width() {
+                    let num = rng.random_range(1..=max);
+                    let should_place = rng.random_bool(1. / 3.);
+                    let index = board.index((col, row));
+
+                    if should_place && board.placement_error(index, Some(num)).is_none() {
+                        let _ = board.place(index, Some(num));
+                    }
+                }
+            }
+        }
+        Strategy::Unique(difficulty) => {
+            board = generate_puzzle(empty, difficulty, Symmetry::None, &mut rng)?
+        }
+        Strategy::Symmetric(difficulty, symmetry) => {
+            board = generate_puzzle(empty, difficulty, symmetry, &mut rng)?
+        }
+        Strategy::Minimal(difficulty) => board = generate_minimal(empty, difficulty, &mut rng)?,
+        Strategy::Killer { difficulty, givens } => {
+            board = generate_killer(empty, difficulty, givens, &mut rng)?
+        }
+        Strategy::Jigsaw(difficulty) => board = generate_jigsaw(empty, difficulty, &mut rng)?,
+        Strategy::Comparison(difficulty) => {
+            board = generate_comparison(empty, difficulty, &mut rng)?
+        }
+    }
+    board.lock_givens();
+
+    Some(board)
+}
+
+/// Remove clues from a random solution for as long as the puzzle stays unique
+/// and no harder than `difficulty`, retrying until the difficulty is reached.
+/// Clues are removed a whole orbit of the symmetry at a time.
+/// After `ATTEMPTS` tries the hardest puzzle found is returned.
+/// `None` if no board follows the rules of `empty`.
+pub fn generate_puzzle(
+    empty: &Board,
+    difficulty: Difficulty,
+    symmetry: Symmetry,
+    rng: &mut impl Rng,
+) -> Option<Board> {
+    let mut best: Option<(Difficulty, Board)> = None;
+
+    for _ in 0..ATTEMPTS {
+        let solution = solve::random_solution(empty, rng)?;
+        let (rating, board) = remove_clues(solution, difficulty, symmetry, rng);
+        if rating == difficulty {
+            return Some(board);
+        }
+        if best.as_ref().is_none_or(|(best, _)| rating > *best) {
+            best = Some((rating, board));
+        }
+    }
+    best.map(|(_, board)| board)
+}
+
+/// Like `generate_puzzle`, but every given is needed for a unique solution.
+/// Clues that were only kept to stay under the difficulty are removed too,
+/// so after `ATTEMPTS` tries the puzzle closest to the difficulty is returned.
+pub fn generate_minimal(
+    empty: &Board,
+    difficulty: Difficulty,
+    rng: &mut impl Rng,
+) -> Option<Board> {
+    let mut best: Option<(usize, Board)> = None;
+
+    for _ in 0..ATTEMPTS {
+        let solution = solve::random_solution(empty, rng)?;
+        let (_, board) = remove_clues(solution, difficulty, Symmetry::None, rng);
+        let board = minimize(board);
+
+        let rating = solve::rate(&board).expect("minimized puzzle is unique");
+        let distance = (rating as usize).abs_diff(difficulty as usize);
+        if distance == 0 {
+            return Some(board);
+        }
+        if best.as_ref().is_none_or(|(best, _)| distance < *best) {
+            best = Some((distance, board));
+        }
+    }
+    best.map(|(_, board)| board)
+}
+
+/// Like `generate_puzzle`, with cages. Without `givens`, cages that don't make a unique
+/// puzzle on their own are thrown away, and the puzzle closest to the difficulty is returned.
+/// If no cages were ever unique on their own, the last attempt keeps some givens.
+pub fn generate_killer(
+    empty: &Board,
+    difficulty: Difficulty,
+    givens: bool,
+    rng: &mut impl Rng,
+) -> Option<Board> {
+    let mut best: Option<(usize, Board)> = None;
+
+    for attempt in 0..ATTEMPTS {
+        let mut solution = solve::random_solution(empty, rng)?;
+        solution.cages = solution.random_cages(MAX_CAGE, rng);
+
+        let (rating, board) = if givens || attempt + 1 == ATTEMPTS && best.is_none() {
+            remove_clues(solution, difficulty, Symmetry::None, rng)
+        } else {
+            let mut board = solution;
+            board.cells.fill(None);
+            board.lock_givens();
+            match solve::rate(&board) {
+                Some(rating) => (rating, board),
+                None => continue,
+            }
+        };
+
+        let distance = (rating as usize).abs_diff(difficulty as usize);
+        if distance == 0 {
+            return Some(board);
+        }
+        if best.as_ref().is_none_or(|(best, _)| distance < *best) {
+            best = Some((distance, board));
+        }
+    }
+    best.map(|(_, board)| board)
+}
+
+/// Like `generate_puzzle`, with a new random layout of regions for every solution
+pub fn generate_jigsaw(empty: &Board, difficulty: Difficulty, rng: &mut impl Rng) -> Option<Board> {
+    let mut best: Option<(Difficulty, Board)> = None;
+
+    for _ in 0..ATTEMPTS {
+        let mut solution = solve::random_solution(empty, rng)?;
+        solution.regions = Some(solution.random_regions(rng));
+        let (rating, board) = remove_clues(solution, difficulty, Symmetry::None, rng);
+        if rating == difficulty {
+            return Some(board);
+        }
+        if best.as_ref().is_none_or(|(best, _)| rating > *best) {
+            best = Some((rating, board));
+        }
+    }
+    best.map(|(_, board)| board)
+}
+
+/// Like `generate_puzzle`, with comparisons that follow every solution
+pub fn generate_comparison(
+    empty: &Board,
+    difficulty: Difficulty,
+    rng: &mut impl Rng,
+) -> Option<Board> {
+    let mut best: Option<(Difficulty, Board)> = None;
+
+    for _ in 0..ATTEMPTS {
+        let mut solution = solve::random_solution(empty, rng)?;
+        let comparisons = Edge::comparisons(&solution);
+        solution.constraints.extend(comparisons);
+        let (rating, board) = remove_clues(solution, difficulty, Symmetry::None, rng);
+        if rating == difficulty {
+            return Some(board);
+        }
+        if best.as_ref().is_none_or(|(best, _)| rating > *best) {
+            best = Some((rating, board));
+        }
+    }
+    best.map(|(_, board)| board)
+}
+
+/// remove every clue that isn't needed for a unique solution
+fn minimize(mut board: Board) -> Board {
+    for index in 0..board.size() {
+        let Some(num) = board.cells[index] else {
+            continue;
+        };
+        board.cells[index] = None;
+
+        if !matches!(solve::solutions(&board), Solutions::Unique(_)) {
+            board.cells[index] = Some(num);
+        }
+    }
+    board.lock_givens();
+
+    board
+}
+
+fn remove_clues(
+    mut board: Board,
+    max: Difficulty,
+    symmetry: Symmetry,
+    rng: &mut impl Rng,
+) -> (Difficulty, Board) {
+    let mut orbits = symmetry.orbits(board.width());
+    orbits.shuffle(rng);
+
+    let mut rating = Difficulty::Easy;
+    for orbit in orbits {
+        let before = board.clone();
+        for &index in &orbit {
+            board.cells[index] = None;
+        }
+
+        match solve::rate_up_to(&board, max) {
+            Some(new_rating) => rating = new_rating,
+            _ => board = before,
+        }
+    }
+    board.lock_givens();
+
+    (rating, board)
+}
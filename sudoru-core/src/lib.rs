@@ -0,0 +1,23 @@
+//! The sudoku engine behind sudoru: boards, solving, rating and generation.
+//! Has no graphics dependencies, so other tools can use it directly.
+
+pub mod batch;
+pub mod board;
+pub mod constraint;
+pub mod daily;
+pub mod format;
+pub mod fpuzzles;
+pub mod gattai;
+pub mod generate;
+pub mod seed;
+pub mod solve;
+pub mod symmetry;
+mod test;
+
+pub use board::{Board, Cell, ParseError, PlaceError, Position, Shape};
+pub use constraint::Constraint;
+pub use daily::{Daily, Date};
+pub use generate::{Strategy, generate_board, generate_from, generate_puzzle};
+pub use seed::Seed;
+pub use solve::{Difficulty, Solutions};
+pub use symmetry::Symmetry;
@@ -0,0 +1,68 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Seed for reproducible generation.
+/// Displayed as a short code players can share, in Crockford base32.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Seed(pub u64);
+
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// characters needed for 64 bits
+const CODE_LEN: usize = 13;
+
+impl Seed {
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+
+    /// ChaCha8 gives the same numbers on every platform and rand version,
+    /// unlike `StdRng`.
+    pub fn rng(self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.0)
+    }
+}
+
+impl Display for Seed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let code: String = (0..CODE_LEN)
+            .rev()
+            .map(|i| ALPHABET[(self.0 >> (i * 5)) as usize & 31] as char)
+            .collect();
+        f.write_str(&code)
+    }
+}
+
+impl FromStr for Seed {
+    type Err = String;
+
+    /// case insensitive, `-` is ignored and `O`, `I` and `L` are read as `0`, `1`, `1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut seed: u64 = 0;
+        let mut len = 0;
+
+        for c in s.chars().filter(|c| *c != '-') {
+            let c = match c.to_ascii_uppercase() {
+                'O' => '0',
+                'I' | 'L' => '1',
+                c => c,
+            };
+            let digit = ALPHABET
+                .iter()
+                .position(|a| *a as char == c)
+                .ok_or_else(|| format!("invalid character {c:?} in seed"))?;
+
+            seed = seed
+                .checked_mul(32)
+                .map(|seed| seed | digit as u64)
+                .ok_or("seed is too long")?;
+            len += 1;
+        }
+
+        match len {
+            0 => Err("empty seed".to_owned()),
+            _ => Ok(Self(seed)),
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::*;
+use crate::board::cage_options;
+
+/// How hard a puzzle is for a person,
+/// based on the hardest technique needed to solve it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// naked and hidden singles, and the ways a cage can add up
+    Easy,
+    /// pointing and claiming candidates
+    Medium,
+    /// naked and hidden pairs / triples
+    Hard,
+    /// needs more than the techniques above
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Self::Easy, Self::Medium, Self::Hard, Self::Expert];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Easy => "easy",
+            Self::Medium => "medium",
+            Self::Hard => "hard",
+            Self::Expert => "expert",
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|d| d.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown difficulty {s:?}"))
+    }
+}
+
+/// Solves a board the way a person would, with pencil marks
+struct Logic {
+    board: Board,
+    all: Candidates,
+    candidates: Vec<Candidates>,
+    units: Vec<Vec<usize>>,
+}
+
+impl Logic {
+    fn new(board: &Board) -> Self {
+        let all = all(board.width());
+        let mut logic = Self {
+            board: board.clone(),
+            all,
+            candidates: (0..board.size())
+                .map(|i| board.allowed(i) & fitting_cage(board, i))
+                .collect(),
+            units: board.units(),
+        };
+        for index in 0..board.size() {
+            if let Some(num) = board.cells[index] {
+                logic.fill(index, num);
+            }
+        }
+        logic
+    }
+
+    /// cells sharing a unit or a cage with `index` or kept apart from it by a constraint,
+    /// some more than once
+    fn peers(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let cages = self.board.cages.iter().map(|cage| &cage.cells);
+        self.units
+            .iter()
+            .chain(cages)
+            .filter(move |unit| unit.contains(&index))
+            .flatten()
+            .copied()
+            .filter(move |&i| i != index)
+            .chain(self.board.constraint_peers(index))
+    }
+
+    fn fill(&mut self, index: usize, num: u8) {
+        self.board.cells[index] = Some(num);
+        self.candidates[index] = 0;
+
+        let bit = 1 << (num - 1);
+        let peers: Vec<usize> = self.peers(index).collect();
+        for peer in peers {
+            self.candidates[peer] &= !bit;
+        }
+        // constraints like non-consecutive depend on the numbers around a cell
+        if !self.board.constraints.is_empty() {
+            for i in 0..self.board.size() {
+                self.candidates[i] &= self.board.allowed(i);
+            }
+        }
+        // and what's left of a cage's sum on the numbers in it
+        if let Some(cage) = self.board.cage(index) {
+            for &i in &cage.cells {
+                self.candidates[i] &= cage.fitting_in(&self.board, i);
+            }
+        }
+    }
+
+    /// remove candidates from cells, true if anything changed
+    fn eliminate(&mut self, cells: impl IntoIterator<Item = usize>, bits: Candidates) -> bool {
+        let mut changed = false;
+        for index in cells {
+            if self.candidates[index] & bits != 0 {
+                self.candidates[index] &= !bits;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn naked_single(&mut self) -> bool {
+        for index in 0..self.board.size() {
+            let c = self.candidates[index];
+            if self.board.cells[index].is_none() && c.count_ones() == 1 {
+                self.fill(index, c.trailing_zeros() as u8 + 1);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn hidden_single(&mut self) -> bool {
+        for u in 0..self.units.len() {
+            let unit = self.units[u].clone();
+            for num in 1..=self.board.width() as u8 {
+                let bit = 1 << (num - 1);
+                let mut cells = unit.iter().filter(|&&i| self.candidates[i] & bit != 0);
+
+                if let (Some(&index), None) = (cells.next(), cells.next()) {
+                    self.fill(index, num);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// candidates that aren't part of any way to make up a cage's sum
+    fn cage_combinations(&mut self) -> bool {
+        let mut changed = false;
+        for c in 0..self.board.cages.len() {
+            let cage = &self.board.cages[c];
+            let options: Vec<Candidates> = cage
+                .cells
+                .iter()
+                .map(|&i| match self.board.cells[i] {
+                    Some(num) => 1 << (num - 1),
+                    None => self.candidates[i],
+                })
+                .collect();
+            let possible = cage_options(&options, cage.sum);
+
+            for (&index, possible) in cage.cells.clone().iter().zip(possible) {
+                changed |= self.eliminate([index], !possible & self.all);
+            }
+            if changed {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// a number confined to one line inside a segment (pointing),
+    /// or to one segment inside a line (claiming)
+    fn locked_candidates(&mut self) -> bool {
+        for a in 0..self.units.len() {
+            for b in 0..self.units.len() {
+                // a line and a segment share a whole segment row or column,
+                // any other pair shares one cell at most
+                let shared = self.units[a]
+                    .iter()
+                    .filter(|i| self.units[b].contains(i))
+                    .count();
+                if a == b || shared < 2 {
+                    continue;
+                }
+                let (unit, other) = (self.units[a].clone(), self.units[b].clone());
+
+                for bit in (0..self.board.width()).map(|n| 1 << n) {
+                    let inside = unit
+                        .iter()
+                        .any(|i| other.contains(i) && self.candidates[*i] & bit != 0);
+                    let outside = unit
+                        .iter()
+                        .any(|i| !other.contains(i) && self.candidates[*i] & bit != 0);
+
+                    if inside && !outside {
+                        let rest = other.iter().copied().filter(|i| !unit.contains(i));
+                        if self.eliminate(rest, bit) {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// `size` cells in a unit that share `size` candidates
+    fn naked_subset(&mut self, size: u32) -> bool {
+        for u in 0..self.units.len() {
+            let open: Vec<usize> = self.units[u]
+                .iter()
+                .copied()
+                .filter(|&i| self.candidates[i] != 0)
+                .collect();
+
+            for combo in combinations(open.len(), size as usize) {
+                let union = combo
+                    .iter()
+                    .fold(0, |acc, &i| acc | self.candidates[open[i]]);
+                if union.count_ones() != size {
+                    continue;
+                }
+                let rest = open
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| !combo.contains(i))
+                    .map(|(_, &cell)| cell);
+                if self.eliminate(rest, union) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// `size` numbers in a unit that only fit in `size` cells
+    fn hidden_subset(&mut self, size: u32) -> bool {
+        for u in 0..self.units.len() {
+            let unit = self.units[u].clone();
+            let open: Vec<u8> = (0..self.board.width() as u8)
+                .filter(|n| unit.iter().any(|&i| self.candidates[i] & (1 << n) != 0))
+                .collect();
+
+            for combo in combinations(open.len(), size as usize) {
+                let bits: Candidates = combo.iter().fold(0, |acc, &n| acc | 1 << open[n]);
+                let cells: Vec<usize> = unit
+                    .iter()
+                    .copied()
+                    .filter(|&i| self.candidates[i] & bits != 0)
+                    .collect();
+
+                if cells.len() == size as usize && self.eliminate(cells, !bits & self.all) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// apply the easiest technique, no harder than `max`, that makes progress
+    fn step(&mut self, max: Difficulty) -> Option<Difficulty> {
+        if self.naked_single() || self.hidden_single() || self.cage_combinations() {
+            Some(Difficulty::Easy)
+        } else if max >= Difficulty::Medium && self.locked_candidates() {
+            Some(Difficulty::Medium)
+        } else if max >= Difficulty::Hard
+            && (2..=3).any(|size| self.naked_subset(size) || self.hidden_subset(size))
+        {
+            Some(Difficulty::Hard)
+        } else {
+            None
+        }
+    }
+}
+
+/// numbers cell `index` can hold for the sum of its cage, all of them outside cages
+fn fitting_cage(board: &Board, index: usize) -> Candidates {
+    match board.cage(index) {
+        Some(cage) => cage.fitting_in(board, index),
+        None => all(board.width()),
+    }
+}
+
+/// every way to pick `k` of `n` indices
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    if n < k {
+        return vec![];
+    }
+    let mut with_last = combinations(n - 1, k - 1);
+    for combo in &mut with_last {
+        combo.push(n - 1);
+    }
+    let mut result = combinations(n - 1, k);
+    result.extend(with_last);
+    result
+}
+
+/// Rate a puzzle by solving it step by step.
+/// `None` if it has no unique solution.
+pub fn rate(board: &Board) -> Option<Difficulty> {
+    rate_up_to(board, Difficulty::Expert)
+}
+
+/// Like `rate`, but `None` as soon as the puzzle turns out harder than `max`,
+/// which saves trying harder techniques on large boards.
+pub fn rate_up_to(board: &Board, max: Difficulty) -> Option<Difficulty> {
+    // the givens already break a rule
+    Solver::new(board)?;
+    let mut logic = Logic::new(board);
+    let mut hardest = Difficulty::Easy;
+
+    // every step is a sound deduction, so a puzzle the techniques
+    // fill in completely can only have one solution
+    while !logic.board.is_solved() {
+        match logic.step(max) {
+            Some(difficulty) => hardest = hardest.max(difficulty),
+            None if max < Difficulty::Expert => return None,
+            None => {
+                return matches!(solutions(board), Solutions::Unique(_))
+                    .then_some(Difficulty::Expert);
+            }
+        }
+    }
+    let sums = logic.board.cages.iter().all(|cage| {
+        let sum: u32 = cage
+            .cells
+            .iter()
+            .filter_map(|&i| logic.board.cells[i])
+            .map(u32::from)
+            .sum();
+        sum == cage.sum
+    });
+    sums.then_some(hardest)
+}
@@ -0,0 +1,313 @@
+mod logic;
+
+pub use logic::{Difficulty, rate, rate_up_to};
+
+use rand::{Rng, seq::SliceRandom};
+
+use crate::board::Board;
+
+/// bitmask of the numbers 1..=25, bit `n - 1` is set for `n`
+pub type Candidates = u32;
+
+/// every number of a board with `side` numbers
+pub const fn all(side: usize) -> Candidates {
+    (1 << side) - 1
+}
+
+/// The result of looking for solutions
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Solutions {
+    None,
+    Unique(Board),
+    Multiple,
+}
+
+/// Backtracking solver, keeping track of which numbers are used in every
+/// unit (see `Board::units`), every cage and every pair of cells that
+/// constraints keep apart, none of which can repeat a number either.
+struct Solver {
+    board: Board,
+    all: Candidates,
+    /// the units, followed by the cells of every cage and the constraints' pairs of peers
+    units: Vec<Vec<usize>>,
+    /// how many of `units` hold every number
+    complete: usize,
+    /// the units every cell is in
+    cell_units: Vec<Vec<usize>>,
+    /// the cage every cell is in
+    cell_cage: Vec<Option<usize>>,
+    /// numbers used in every unit
+    used: Vec<Candidates>,
+    /// look for hidden singles in `next_choice`
+    hidden: bool,
+}
+
+impl Solver {
+    /// `None` if the board already breaks a rule
+    fn new(board: &Board) -> Option<Self> {
+        let mut units = board.units();
+        let complete = units.len();
+        units.extend(board.cages.iter().map(|cage| cage.cells.clone()));
+        for index in 0..board.size() {
+            for peer in board.constraint_peers(index) {
+                if index < peer {
+                    units.push(vec![index, peer]);
+                }
+            }
+        }
+
+        let mut cell_cage = vec![None; board.size()];
+        for (c, cage) in board.cages.iter().enumerate() {
+            for &index in &cage.cells {
+                cell_cage[index] = Some(c);
+            }
+        }
+        let mut cell_units = vec![Vec::new(); board.size()];
+        for (u, unit) in units.iter().enumerate() {
+            for &index in unit {
+                cell_units[index].push(u);
+            }
+        }
+
+        let mut solver = Self {
+            board: board.clone(),
+            all: all(board.width()),
+            used: vec![0; units.len()],
+            units,
+            complete,
+            cell_units,
+            cell_cage,
+            hidden: false,
+        };
+
+        for index in 0..board.size() {
+            if let Some(num) = board.cells[index] {
+                let bit = 1 << (num - 1);
+                if solver.used(index) & bit != 0 || board.allowed(index) & bit == 0 {
+                    return None;
+                }
+                solver.toggle(index, bit);
+            }
+        }
+        let sums_fit = board.cages.iter().all(|cage| {
+            let full = cage.cells.iter().all(|&i| board.cells[i].is_some());
+            let sum: u32 = cage
+                .cells
+                .iter()
+                .filter_map(|&i| board.cells[i])
+                .map(u32::from)
+                .sum();
+            if full {
+                sum == cage.sum
+            } else {
+                sum < cage.sum
+            }
+        });
+        sums_fit.then_some(solver)
+    }
+
+    /// numbers that can go in empty cell `index`
+    fn candidates(&self, index: usize) -> Candidates {
+        let mut candidates = self.all & !self.used(index);
+        if !self.board.constraints.is_empty() {
+            candidates &= self.board.allowed(index);
+        }
+        match self.cell_cage[index] {
+            Some(c) => candidates & self.board.cages[c].fitting_in(&self.board, index),
+            None => candidates,
+        }
+    }
+
+    fn used(&self, index: usize) -> Candidates {
+        self.cell_units[index]
+            .iter()
+            .fold(0, |used, &u| used | self.used[u])
+    }
+
+    fn toggle(&mut self, index: usize, bit: Candidates) {
+        for &u in &self.cell_units[index] {
+            self.used[u] ^= bit;
+        }
+    }
+
+    /// empty cell with the fewest candidates
+    fn most_constrained(&self) -> Option<(usize, Candidates)> {
+        let mut best: Option<(usize, Candidates)> = None;
+
+        for index in 0..self.board.size() {
+            if self.board.cells[index].is_some() {
+                continue;
+            }
+            let candidates = self.candidates(index);
+            let count = candidates.count_ones();
+
+            if best.is_none_or(|(_, c)| count < c.count_ones()) {
+                best = Some((index, candidates));
+                if count <= 1 {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    /// Like `most_constrained`, but a number that only fits in one cell of a unit
+    /// comes first, and a number that fits nowhere gives no candidates at all.
+    /// Prunes much more on large boards, but changes the order solutions are found in.
+    fn next_choice(&self) -> Option<(usize, Candidates)> {
+        let best = self.most_constrained()?;
+        if !self.hidden || best.1.count_ones() <= 1 {
+            return Some(best);
+        }
+
+        for unit in &self.units[..self.complete] {
+            let (mut once, mut twice, mut filled) = (0, 0, 0);
+            for &index in unit {
+                match self.board.cells[index] {
+                    Some(num) => filled |= 1 << (num - 1),
+                    None => {
+                        let candidates = self.candidates(index);
+                        twice |= once & candidates;
+                        once |= candidates;
+                    }
+                }
+            }
+
+            if once | filled != self.all {
+                return Some((best.0, 0));
+            }
+            let single = once & !twice;
+            if single != 0 {
+                let bit = single & single.wrapping_neg();
+                let index = unit
+                    .iter()
+                    .copied()
+                    .find(|&i| self.board.cells[i].is_none() && self.candidates(i) & bit != 0)
+                    .expect("a hidden single has a cell");
+                return Some((index, bit));
+            }
+        }
+        Some(best)
+    }
+
+    /// Calls `found` for every solution until it returns false.
+    /// `order` decides in which order candidates are tried.
+    fn search(
+        &mut self,
+        order: &mut impl FnMut(Candidates) -> Vec<u8>,
+        found: &mut impl FnMut(&Board) -> bool,
+    ) -> bool {
+        let Some((index, candidates)) = self.next_choice() else {
+            return found(&self.board);
+        };
+
+        for num in order(candidates) {
+            let bit = 1 << (num - 1);
+            self.board.cells[index] = Some(num);
+            self.toggle(index, bit);
+
+            let go_on = self.search(order, found);
+
+            self.toggle(index, bit);
+            self.board.cells[index] = None;
+
+            if !go_on {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// the numbers in a set of candidates, lowest first
+pub fn numbers(candidates: Candidates) -> Vec<u8> {
+    (1..=Candidates::BITS as u8)
+        .filter(|n| candidates & (1 << (n - 1)) != 0)
+        .collect()
+}
+
+pub fn solutions(board: &Board) -> Solutions {
+    let Some(mut solver) = Solver::new(board) else {
+        return Solutions::None;
+    };
+    solver.hidden = true;
+    let mut result = Solutions::None;
+
+    solver.search(&mut numbers, &mut |solution| {
+        if result == Solutions::None {
+            result = Solutions::Unique(solution.clone());
+            true
+        } else {
+            result = Solutions::Multiple;
+            false
+        }
+    });
+    result
+}
+
+impl Board {
+    /// Has a unique solution, and removing any filled cell
+    /// gives it more than one
+    pub fn is_minimal(&self) -> bool {
+        if !matches!(solutions(self), Solutions::Unique(_)) {
+            return false;
+        }
+
+        let mut board = self.clone();
+        (0..self.size()).all(|index| {
+            let Some(num) = board.cells[index] else {
+                return true;
+            };
+            board.cells[index] = None;
+            let needed = solutions(&board) == Solutions::Multiple;
+            board.cells[index] = Some(num);
+            needed
+        })
+    }
+}
+
+impl Board {
+    /// A solution of the givens, whatever else the player filled in.
+    /// Hints come from it.
+    pub fn solution(&self) -> Option<Board> {
+        let mut puzzle = self.clone();
+        for (cell, given) in puzzle.cells.iter_mut().zip(&self.givens) {
+            if !given {
+                *cell = None;
+            }
+        }
+        solve(&puzzle)
+    }
+}
+
+/// any solution of the board
+pub fn solve(board: &Board) -> Option<Board> {
+    solve_random(board, &mut |_| {})
+}
+
+/// A solution where candidates are tried in the order given by `shuffle`.
+/// Used to fill a board randomly.
+pub fn solve_random(board: &Board, shuffle: &mut impl FnMut(&mut [u8])) -> Option<Board> {
+    let mut solver = Solver::new(board)?;
+    let mut solution = None;
+
+    solver.search(
+        &mut |candidates| {
+            let mut nums = numbers(candidates);
+            shuffle(&mut nums);
+            nums
+        },
+        &mut |board| {
+            solution = Some(board.clone());
+            false
+        },
+    );
+    solution
+}
+
+/// A random, completely filled board with the shape and rules of `board`,
+/// `None` if the rules leave no way to fill it (anti-king on a 4×4 board).
+pub fn random_solution(board: &Board, rng: &mut impl Rng) -> Option<Board> {
+    solve_random(&board.empty(), &mut |nums| nums.shuffle(rng))
+}
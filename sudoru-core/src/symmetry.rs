@@ -0,0 +1,110 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::board::Position;
+
+/// Symmetry of the givens in a generated puzzle
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// the same after turning the board upside down
+    Rotational180,
+    /// the same after a quarter turn
+    Rotational90,
+    /// mirrored across the middle row
+    Horizontal,
+    /// mirrored across the middle column
+    Vertical,
+    /// mirrored across the top-left to bottom-right diagonal
+    Diagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 6] = [
+        Self::None,
+        Self::Rotational180,
+        Self::Rotational90,
+        Self::Horizontal,
+        Self::Vertical,
+        Self::Diagonal,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Rotational180 => "rotational",
+            Self::Rotational90 => "rotational90",
+            Self::Horizontal => "horizontal",
+            Self::Vertical => "vertical",
+            Self::Diagonal => "diagonal",
+        }
+    }
+
+    /// the cell that `pos` maps to on a board `side` cells wide
+    fn map(self, pos: impl Position, side: usize) -> usize {
+        let (x, y) = pos.coords(side);
+        let (right, bottom) = (side - 1, side - 1);
+
+        match self {
+            Self::None => (x, y),
+            Self::Rotational180 => (right - x, bottom - y),
+            Self::Rotational90 => (bottom - y, x),
+            Self::Horizontal => (x, bottom - y),
+            Self::Vertical => (right - x, y),
+            Self::Diagonal => (y, x),
+        }
+        .index(side)
+    }
+
+    /// all cells that `index` maps to when applied repeatedly, starting with `index`
+    pub fn orbit(self, index: usize, side: usize) -> Vec<usize> {
+        let mut orbit = vec![index];
+        let mut next = self.map(index, side);
+
+        while next != index {
+            orbit.push(next);
+            next = self.map(next, side);
+        }
+        orbit
+    }
+
+    /// a board `side` cells wide split into orbits, ordered by their first cell
+    pub fn orbits(self, side: usize) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; side * side];
+        let mut orbits = Vec::new();
+
+        for index in 0..side * side {
+            if seen[index] {
+                continue;
+            }
+            let orbit = self.orbit(index, side);
+            for &i in &orbit {
+                seen[i] = true;
+            }
+            orbits.push(orbit);
+        }
+        orbits
+    }
+
+    /// whether the filled cells of a board `side` cells wide follow this symmetry
+    pub fn matches<T>(self, side: usize, cells: &[Option<T>]) -> bool {
+        (0..side * side).all(|i| cells[i].is_some() == cells[self.map(i, side)].is_some())
+    }
+}
+
+impl Display for Symmetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|sym| sym.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown symmetry {s:?}"))
+    }
+}
@@ -0,0 +1,1093 @@
+#[cfg(test)]
+mod tests {
+    use crate::batch::generate_batch;
+    use crate::board::{
+        Board, Cage, ParseError, Pencil, PlaceError, Position, Shape, TransformError, cage_options,
+    };
+    use crate::constraint::{self, EdgeKind, Mark};
+    use crate::daily::{Daily, Date};
+    use crate::format::{self, Format};
+    use crate::fpuzzles;
+    use crate::gattai::{Gattai, Layout};
+    use crate::generate::{Strategy, generate_board, generate_from};
+    use crate::seed::Seed;
+    use crate::solve::{self, Difficulty, Solutions};
+    use crate::symmetry::Symmetry;
+
+    #[test]
+    fn board_row() {
+        let mut board = Board::new();
+
+        for i in 1..9 {
+            board.cells[i] = Some(i as u8);
+        }
+
+        let row = board.row(0);
+
+        assert_eq!(
+            row,
+            &[
+                None,
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(4),
+                Some(5),
+                Some(6),
+                Some(7),
+                Some(8),
+            ]
+        );
+    }
+
+    #[test]
+    fn board_col() {
+        let mut board = Board::new();
+
+        for i in 1..9 {
+            board.set((1, i), Some(i as u8));
+        }
+
+        let col = board.col(1);
+
+        assert_eq!(
+            &col,
+            &[
+                None,
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(4),
+                Some(5),
+                Some(6),
+                Some(7),
+                Some(8),
+            ]
+        );
+    }
+
+    #[test]
+    fn board_insert() {
+        let mut board = Board::new();
+        let value = Some(1);
+
+        board.cells[0] = value;
+
+        let result = board.place_at((2, 0), value);
+        assert_eq!(result, Err(PlaceError::AlreadyInRow));
+
+        let result = board.place_at((0, 2), value);
+        assert_eq!(result, Err(PlaceError::AlreadyInCol));
+
+        let result = board.place_at((1, 1), value);
+        assert_eq!(result, Err(PlaceError::AlreadyInSeg));
+    }
+
+    #[test]
+    fn board_get() {
+        let mut board = Board::new();
+        let value = Some(2);
+
+        board.cells[10] = value;
+
+        assert_eq!(board.get((1, 1)), Some(&value));
+
+        let value = Some(3);
+        board.set((3, 3), value);
+
+        assert_eq!(board.get((3, 3)), Some(&value));
+    }
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn board_parse() {
+        let board: Board = PUZZLE.parse().unwrap();
+
+        assert_eq!(board.get((0, 0)), Some(&Some(5)));
+        assert_eq!(board.get((2, 0)), Some(&None));
+        assert!(board.givens[(1, 0).index(9)]);
+        assert!(!board.givens[(2, 0).index(9)]);
+        assert_eq!(board.line(), PUZZLE);
+
+        assert_eq!("123".parse::<Board>(), Err(ParseError::Length(3)));
+        let invalid = PUZZLE.replace('7', "x");
+        assert_eq!(invalid.parse::<Board>(), Err(ParseError::InvalidChar('x')));
+    }
+
+    #[test]
+    fn board_givens() {
+        let mut board: Board = PUZZLE.parse().unwrap();
+
+        assert_eq!(board.place((0, 0), Some(1)), Err(PlaceError::Given));
+        assert_eq!(board.place((2, 0), Some(4)), Ok(()));
+    }
+
+    #[test]
+    fn lines_parse() {
+        let text = format!("# header\n\n{PUZZLE} 1.2\nnot a puzzle\n{PUZZLE}\n");
+        let (puzzles, errors) = format::parse(&text);
+
+        assert_eq!(errors, vec![(4, ParseError::Length(3))]);
+        assert!(Format::Line.read(&text).is_err());
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0].comment, "1.2");
+        assert_eq!(puzzles[1].comment, "");
+        assert_eq!(puzzles[1].board.line(), PUZZLE);
+    }
+
+    #[test]
+    fn first_replaced() {
+        let text = format!("# header\nnot a puzzle\n\n{PUZZLE} 1.2\n{PUZZLE}\n");
+        let saved = format::replace_first(&text, &format!("{SOLUTION}\n"));
+
+        assert_eq!(
+            saved,
+            format!("# header\nnot a puzzle\n\n{SOLUTION}\n{PUZZLE}\n")
+        );
+        let (puzzles, _) = format::parse(&saved);
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(
+            format::replace_first("# header", PUZZLE),
+            format!("# header\n{PUZZLE}\n")
+        );
+    }
+
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn solve_unique() {
+        let board: Board = PUZZLE.parse().unwrap();
+
+        let Solutions::Unique(solution) = solve::solutions(&board) else {
+            panic!("expected a unique solution");
+        };
+        assert_eq!(solution.line(), SOLUTION);
+        assert!(solution.is_solved());
+
+        assert_eq!(solve::solutions(&Board::new()), Solutions::Multiple);
+    }
+
+    #[test]
+    fn rate_puzzle() {
+        let board: Board = PUZZLE.parse().unwrap();
+        assert_eq!(solve::rate(&board), Some(Difficulty::Easy));
+        assert_eq!(solve::rate(&Board::new()), None);
+
+        assert_eq!("Hard".parse(), Ok(Difficulty::Hard));
+        assert!("impossible".parse::<Difficulty>().is_err());
+    }
+
+    #[test]
+    fn format_roundtrip() {
+        let board: Board = PUZZLE.parse().unwrap();
+
+        for format in [Format::Line, Format::Sdk, Format::Grid] {
+            let text = format!("{0}{1}{0}", format.write(&board), format.separator());
+            let boards = format.read(&text).unwrap();
+
+            assert_eq!(boards.len(), 2, "{format:?}");
+            assert_eq!(boards[1].line(), PUZZLE, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn seed_code() {
+        let seed = Seed(0x0123_4567_89ab_cdef);
+        let code = seed.to_string();
+
+        assert_eq!(code.len(), 13);
+        assert_eq!(code.parse(), Ok(seed));
+        assert_eq!(code.to_lowercase().parse(), Ok(seed));
+        assert_eq!("0000-0000-0000-3".parse(), Ok(Seed(3)));
+        assert_eq!("oil".parse(), Ok(Seed(33)));
+
+        assert!("".parse::<Seed>().is_err());
+        assert!("U".parse::<Seed>().is_err());
+        assert!("ZZZZZZZZZZZZZZ".parse::<Seed>().is_err());
+    }
+
+    #[test]
+    fn seeded_generation() {
+        let pinned = [
+            (
+                Seed(1),
+                Difficulty::Easy,
+                "4.....57867......3.....8.....1.6.89...2..9.....7.8.2.....59...7.1.....6...3..7...",
+            ),
+            (
+                Seed(2),
+                Difficulty::Medium,
+                "..5....72..9...1...643..5.....5....6.4..2.7..6..97..2..7.....63...4.....53.68....",
+            ),
+            (
+                Seed(3),
+                Difficulty::Hard,
+                "....385....7..9.24...7.......3.........6....97.21.4.362..34..1....8.7..36........",
+            ),
+        ];
+
+        for (seed, difficulty, expected) in pinned {
+            let board = generate_board(Strategy::Unique(difficulty), seed);
+            assert_eq!(board.line(), expected, "{seed}");
+            assert_eq!(solve::rate(&board), Some(difficulty));
+        }
+
+        let sparse = generate_board(Strategy::TryRandomSparse, Seed(4));
+        assert_eq!(sparse, generate_board(Strategy::TryRandomSparse, Seed(4)));
+        assert_ne!(sparse, generate_board(Strategy::TryRandomSparse, Seed(5)));
+    }
+
+    #[test]
+    fn date_days() {
+        assert_eq!(Date::new(1970, 1, 1).days(), 0);
+        assert_eq!(Date::new(2000, 3, 1).days(), 11017);
+        assert_eq!(Date::new(1969, 12, 31).days(), -1);
+
+        for days in [-800_000, -1, 0, 59, 60, 11016, 20_000, 800_000] {
+            assert_eq!(Date::from_days(days).days(), days);
+        }
+
+        assert_eq!(Date::new(1970, 1, 1).weekday(), 3);
+        assert_eq!(Date::new(2000, 2, 29).weekday(), 1);
+        assert_eq!(Date::new(2026, 10, 19).weekday(), 0);
+        assert_eq!(Date::new(2024, 12, 31).add_days(1), Date::new(2025, 1, 1));
+    }
+
+    #[test]
+    fn date_parse() {
+        let date = Date::new(2026, 3, 7);
+        assert_eq!(date.to_string(), "2026-03-07");
+        assert_eq!("2026-03-07".parse(), Ok(date));
+
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2026-13-01".parse::<Date>().is_err());
+        assert!("yesterday".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn daily_puzzle() {
+        let monday = Daily::new(Date::new(2026, 10, 19));
+        let tuesday = Daily::new(Date::new(2026, 10, 20));
+
+        assert_eq!(monday, Daily::new(Date::new(2026, 10, 19)));
+        assert_ne!(monday.seed, tuesday.seed);
+        assert_eq!(monday.difficulty, Difficulty::Easy);
+        assert_eq!(
+            Daily::new(Date::new(2026, 10, 25)).difficulty,
+            Difficulty::Expert
+        );
+
+        assert_eq!(monday.board(), monday.board());
+        assert_eq!(solve::rate(&monday.board()), Some(Difficulty::Easy));
+    }
+
+    #[test]
+    fn symmetry_orbits() {
+        assert_eq!(Symmetry::None.orbits(9).len(), 81);
+        assert_eq!(Symmetry::Rotational180.orbits(9).len(), 41);
+        assert_eq!(Symmetry::Rotational90.orbits(9).len(), 21);
+        assert_eq!(Symmetry::Horizontal.orbits(9).len(), 45);
+        assert_eq!(Symmetry::Diagonal.orbits(9).len(), 45);
+
+        let corner = Symmetry::Rotational90.orbit(0, 9);
+        assert_eq!(corner, vec![0, 8, 80, 72]);
+        assert_eq!(Symmetry::Vertical.orbit((1, 2).index(9), 9), vec![19, 25]);
+        assert_eq!("Diagonal".parse(), Ok(Symmetry::Diagonal));
+    }
+
+    #[test]
+    fn symmetric_generation() {
+        for (i, symmetry) in Symmetry::ALL.into_iter().enumerate() {
+            let strategy = Strategy::Symmetric(Difficulty::Medium, symmetry);
+            let board = generate_board(strategy, Seed(i as u64));
+
+            assert!(symmetry.matches(9, &board.cells), "{symmetry}");
+            assert!(matches!(solve::solutions(&board), Solutions::Unique(_)));
+        }
+    }
+
+    #[test]
+    fn minimal_generation() {
+        let board = generate_board(Strategy::Minimal(Difficulty::Medium), Seed(6));
+        assert!(board.is_minimal());
+
+        let Solutions::Unique(solution) = solve::solutions(&board) else {
+            panic!("expected a unique solution");
+        };
+        let index = board.cells.iter().position(Option::is_none).unwrap();
+        let mut extra = board.clone();
+        extra.set(index, solution.cells[index]);
+
+        assert_eq!(extra.clue_count(), board.clue_count() + 1);
+        assert!(!extra.is_minimal());
+        assert!(!Board::new().is_minimal());
+    }
+
+    #[test]
+    fn batch_generation() {
+        let strategy = Strategy::Unique(Difficulty::Easy);
+        let collect = |threads| {
+            let mut boards = Vec::new();
+            generate_batch(
+                &Board::new(),
+                strategy,
+                6,
+                Seed(10),
+                threads,
+                |seed, board| {
+                    boards.push((seed, board));
+                    true
+                },
+            );
+            boards
+        };
+
+        let single = collect(1);
+        assert_eq!(single.len(), 6);
+        assert_eq!(single, collect(4));
+
+        for (seed, board) in &single {
+            assert_eq!(*board, generate_board(strategy, *seed));
+        }
+
+        let mut first = Vec::new();
+        generate_batch(&Board::new(), strategy, 6, Seed(10), 2, |seed, _| {
+            first.push(seed);
+            false
+        });
+        assert_eq!(first, vec![Seed(10)]);
+
+        // 4×4 boards run out of distinct puzzles
+        let small = Board::with_shape(Shape::new(2, 2));
+        let made = generate_batch(&small, strategy, 1000, Seed(1), 2, |_, _| true);
+        assert!(made.is_some_and(|made| made > 0 && made < 1000), "{made:?}");
+    }
+
+    #[test]
+    fn unsolvable_rules() {
+        // no 4×4 board keeps equal numbers a king's move apart
+        let empty = Board {
+            constraints: vec![constraint::parse("anti-king", 16).unwrap()],
+            ..Board::with_shape(Shape::new(2, 2))
+        };
+        let mut rng = Seed(1).rng();
+        assert_eq!(solve::random_solution(&empty, &mut rng), None);
+
+        let strategy = Strategy::Unique(Difficulty::Easy);
+        assert_eq!(generate_from(&empty, strategy, Seed(1)), None);
+        let made = generate_batch(&empty, strategy, 3, Seed(1), 2, |_, _| true);
+        assert_eq!(made, None);
+        assert_eq!(
+            generate_batch(&Board::new(), strategy, 2, Seed(1), 2, |_, _| true),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn canonical_form() {
+        let board: Board = PUZZLE.parse().unwrap();
+        let canonical = board.canonical();
+
+        let mut changed = Board::new();
+        for index in 0..81 {
+            let (x, y) = index.coords(9);
+            // transpose, swap the first two rows and the last two bands
+            let y = match y {
+                0 => 1,
+                1 => 0,
+                3..=5 => y + 3,
+                6..=8 => y - 3,
+                _ => y,
+            };
+            // swap 1 and 2
+            let cell = board.cells[(y, x).index(9)].map(|num| match num {
+                1 => 2,
+                2 => 1,
+                num => num,
+            });
+            changed.set(index, cell);
+        }
+
+        assert_ne!(changed.line(), PUZZLE);
+        assert_eq!(changed.canonical(), canonical);
+        assert!(changed.is_equivalent(&board));
+
+        let again: Board = canonical.parse().unwrap();
+        assert_eq!(again.canonical(), canonical);
+
+        let mut other = board.clone();
+        other.set(2, Some(4));
+        assert!(!other.is_equivalent(&board));
+    }
+
+    #[test]
+    fn board_transforms() {
+        let board: Board = PUZZLE.parse().unwrap();
+        let solution: Board = SOLUTION.parse().unwrap();
+
+        let transforms: [fn(&Board) -> Board; 9] = [
+            |b| b.rotated().unwrap(),
+            Board::transposed,
+            |b| b.flipped_vertically().unwrap(),
+            |b| b.flipped_horizontally().unwrap(),
+            |b| b.swap_rows(1, 0, 2).unwrap(),
+            |b| b.swap_cols(2, 1, 2).unwrap(),
+            |b| b.swap_bands(0, 2).unwrap(),
+            |b| b.swap_stacks(0, 1).unwrap(),
+            |b| b.relabelled(&[9, 8, 7, 6, 5, 4, 3, 2, 1]).unwrap(),
+        ];
+
+        for transform in transforms {
+            let changed = transform(&board);
+            assert_ne!(changed, board);
+            assert_eq!(changed.canonical(), board.canonical());
+            assert_eq!(changed.clue_count(), board.clue_count());
+
+            let Solutions::Unique(solved) = solve::solutions(&changed) else {
+                panic!("expected a unique solution");
+            };
+            assert_eq!(solved.cells, transform(&solution).cells);
+        }
+
+        let rotated = board.rotated().unwrap();
+        let turned = rotated
+            .rotated()
+            .unwrap()
+            .rotated()
+            .unwrap()
+            .rotated()
+            .unwrap();
+        assert_eq!(turned, board);
+        assert_eq!(rotated.get((8, 0)), board.get((0, 0)));
+        assert!(rotated.givens[8]);
+
+        let shuffled = board.shuffled(&mut Seed(1).rng());
+        assert_ne!(shuffled, board);
+        assert!(shuffled.is_equivalent(&board));
+        assert_eq!(shuffled, board.shuffled(&mut Seed(1).rng()));
+    }
+
+    #[test]
+    fn variant_transforms() {
+        let labels = [9, 8, 7, 6, 5, 4, 3, 2, 1];
+        let killer = Strategy::Killer {
+            difficulty: Difficulty::Easy,
+            givens: true,
+        };
+        let killer = generate_board(killer, Seed(1));
+        assert_eq!(killer.relabelled(&labels), Err(TransformError::Relabel));
+
+        let empty = Board {
+            diagonals: true,
+            constraints: vec![constraint::parse("anti-knight", 81).unwrap()],
+            ..Board::new()
+        };
+        let diagonal = generate_from(&empty, Strategy::Unique(Difficulty::Easy), Seed(1)).unwrap();
+        assert!(diagonal.relabelled(&labels).is_ok());
+        assert_eq!(diagonal.swap_rows(0, 0, 1), Err(TransformError::Move));
+        assert_eq!(diagonal.swap_bands(0, 1), Err(TransformError::Move));
+
+        let jigsaw = generate_board(Strategy::Jigsaw(Difficulty::Easy), Seed(1));
+        let comparison = generate_board(Strategy::Comparison(Difficulty::Easy), Seed(1));
+        for board in [killer, diagonal, jigsaw, comparison] {
+            for seed in 0..4 {
+                let shuffled = board.shuffled(&mut Seed(seed).rng());
+                assert!(matches!(solve::solutions(&shuffled), Solutions::Unique(_)));
+                // regions, cages and constraints still read back
+                let line = Format::Line.write(&shuffled);
+                assert_eq!(format::parse_line(&line).unwrap().board, shuffled, "{line}");
+            }
+        }
+    }
+
+    #[test]
+    fn board_shapes() {
+        let small: Board = "1.3..4.1.1..4..2".parse().unwrap();
+        assert_eq!(small.shape, Shape::new(2, 2));
+        assert_eq!(
+            small.to_string(),
+            "1 . | 3 . \n. 4 | . 1 \n----+----\n. 1 | . . \n4 . | . 2 \n"
+        );
+
+        let six = Shape::new(3, 2);
+        assert_eq!("6x6".parse(), Ok(six));
+        assert_eq!(six.seg_index((4, 3)), 3);
+        assert_eq!(six.segment((4, 3)), (3, 2));
+
+        let mut board = Board::with_shape(six);
+        board.set((0, 0), Some(6));
+        assert_eq!(board.place((2, 1), Some(6)), Err(PlaceError::AlreadyInSeg));
+        assert_eq!(board.place((0, 2), Some(5)), Ok(()));
+
+        let big = Board::with_shape(Shape::new(4, 4));
+        let line = format!("G{}", &big.line()[1..]);
+        let parsed: Board = line.parse().unwrap();
+        assert_eq!(parsed.get(0), Some(&Some(16)));
+        assert_eq!(parsed.line(), line);
+        assert!("7".repeat(16).parse::<Board>().is_err());
+        assert_eq!(
+            "5...............".parse::<Board>(),
+            Err(ParseError::InvalidChar('5'))
+        );
+        assert_eq!(".".repeat(50).parse::<Board>(), Err(ParseError::Length(50)));
+    }
+
+    #[test]
+    fn shaped_generation() {
+        for shape in [Shape::new(2, 2), Shape::new(3, 2), Shape::new(4, 3)] {
+            let empty = Board::with_shape(shape);
+            let board = generate_from(&empty, Strategy::Unique(Difficulty::Easy), Seed(1)).unwrap();
+            assert_eq!(board.shape, shape);
+            assert_eq!(solve::rate(&board), Some(Difficulty::Easy), "{shape}");
+
+            let Solutions::Unique(solution) = solve::solutions(&board) else {
+                panic!("expected a unique solution");
+            };
+            assert!(solution.is_solved());
+
+            // segments that aren't square end up on their side
+            let turned = board.rotated().unwrap();
+            assert_eq!(turned.shape, shape.transposed());
+            assert!(matches!(solve::solutions(&turned), Solutions::Unique(_)));
+            if shape.side() <= 9 {
+                assert!(board.shuffled(&mut Seed(2).rng()).is_equivalent(&board));
+            }
+
+            for format in [Format::Line, Format::Sdk, Format::Grid] {
+                let boards = format.read(&format.write(&board)).unwrap();
+                assert_eq!(boards, vec![board.clone()], "{shape} {format:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn diagonal_rules() {
+        let mut board = Board {
+            diagonals: true,
+            ..Board::new()
+        };
+        board.set((0, 0), Some(5));
+        board.set((8, 0), Some(7));
+        assert_eq!(
+            board.place((4, 4), Some(5)),
+            Err(PlaceError::AlreadyInDiagonal)
+        );
+        assert_eq!(
+            board.place((2, 6), Some(7)),
+            Err(PlaceError::AlreadyInDiagonal)
+        );
+        assert_eq!(board.place((4, 3), Some(5)), Ok(()));
+        assert!(board.diagonal_cells((1, 0)).is_empty());
+        assert_eq!(board.diagonal_cells((4, 4)).len(), 18);
+
+        let board = generate_from(
+            &board.empty(),
+            Strategy::Unique(Difficulty::Medium),
+            Seed(1),
+        )
+        .unwrap();
+        assert!(board.diagonals);
+        let Solutions::Unique(solution) = solve::solutions(&board) else {
+            panic!("expected a unique solution");
+        };
+        for start in [0, 8] {
+            let mut nums: Vec<_> = board
+                .diagonal_cells(start)
+                .into_iter()
+                .map(|i| solution.cells[i])
+                .collect();
+            nums.sort();
+            nums.dedup();
+            assert_eq!(nums.len(), 9);
+        }
+
+        let mut classic = board.clone();
+        classic.diagonals = false;
+        assert_eq!(solve::solutions(&classic), Solutions::Multiple);
+        assert!(board.rotated().unwrap().diagonals);
+
+        let line = Format::Line.write(&board);
+        assert!(line.trim_end().ends_with(" diagonal"), "{line}");
+        assert_eq!(format::parse_line(&line).unwrap().board, board);
+        for format in [Format::Line, Format::FPuzzles] {
+            let read = format.read(&format.write(&board)).unwrap();
+            assert!(read[0].diagonals, "{format:?}");
+        }
+        // rules come right after the grid, the comment after them
+        let line = format!("{} diagonal 5", board.line());
+        let puzzle = format::parse_line(&line).unwrap();
+        assert!(puzzle.board.diagonals);
+        assert_eq!(puzzle.comment, "5");
+        let line = format!("{} easy, no diagonal or cages", board.line());
+        let puzzle = format::parse_line(&line).unwrap();
+        assert!(!puzzle.board.diagonals);
+        assert_eq!(puzzle.comment, "easy, no diagonal or cages");
+    }
+
+    #[test]
+    fn killer_cages() {
+        let mut board = Board::new();
+        board.cages = vec![Cage::new(vec![12, 3, 2], 10), "4:0,9".parse().unwrap()];
+        assert_eq!(board.cages[0].to_string(), "10:2,3,12");
+        assert_eq!(board.cage(12), Some(&board.cages[0]));
+
+        // 2 and 12 are in different rows, columns and segments
+        board.set(2, Some(5));
+        assert_eq!(board.place(12, Some(5)), Err(PlaceError::AlreadyInCage));
+        assert_eq!(board.place(12, Some(6)), Err(PlaceError::CageSum));
+        assert_eq!(board.place(12, Some(4)), Ok(()));
+        assert_eq!(board.place(0, Some(2)), Err(PlaceError::CageSum));
+
+        // 4 in two cells can only be 1 + 3, and the second can't be 1
+        assert_eq!(
+            cage_options(&[0b111_111_111, 0b111_111_110], 4),
+            [0b1, 0b100]
+        );
+
+        let puzzle = format::parse_line(&Format::Line.write(&board)).unwrap();
+        assert_eq!(puzzle.board.cages, board.cages);
+        let outside = format::parse_line(&format!("{} cages 3:0,81", board.line()));
+        assert_eq!(outside.err(), Some(ParseError::InvalidCage));
+
+        let strategy = Strategy::Killer {
+            difficulty: Difficulty::Easy,
+            givens: false,
+        };
+        let board = generate_board(strategy, Seed(1));
+        assert_eq!(board.clue_count(), 0);
+        assert!(matches!(solve::solutions(&board), Solutions::Unique(_)));
+        assert_eq!(board.rotated().unwrap().cages.len(), board.cages.len());
+
+        // the last cell fits the rows, columns and segments, but not its cage
+        let unsolvable = format::parse_line("123434122143432. cages 3:15").unwrap();
+        assert_eq!(solve::solutions(&unsolvable.board), Solutions::None);
+        assert_eq!(solve::rate(&unsolvable.board), None);
+    }
+
+    #[test]
+    fn jigsaw_regions() {
+        let board = generate_board(Strategy::Jigsaw(Difficulty::Medium), Seed(1));
+        let regions = board.regions.clone().unwrap();
+        assert!(board.is_layout(&regions));
+        assert_ne!(
+            board.seg_cells(0),
+            Shape::STANDARD.seg_cells(0).collect::<Vec<_>>()
+        );
+
+        let Solutions::Unique(solution) = solve::solutions(&board) else {
+            panic!("expected a unique solution");
+        };
+        for seg in 0..9 {
+            let mut nums = solution.segment(board.seg_cells(seg)[0]);
+            nums.sort();
+            assert_eq!(nums, (1..=9).map(Some).collect::<Vec<_>>());
+        }
+
+        // two cells of a region that share no row, column or standard segment
+        let cells = board.seg_cells(board.seg_index(0));
+        let other = *cells
+            .iter()
+            .find(|&&i| i / 9 != 0 && i % 9 != 0 && Shape::STANDARD.seg_index(i) != 0)
+            .unwrap();
+        let mut empty = board.empty();
+        empty.set(0, Some(1));
+        assert_eq!(empty.place(other, Some(1)), Err(PlaceError::AlreadyInSeg));
+
+        let puzzle = format::parse_line(&Format::Line.write(&board)).unwrap();
+        assert_eq!(puzzle.board, board);
+        let one_region =
+            format::parse_line(&format!("{} regions {}", "0".repeat(81), "1".repeat(81)));
+        assert_eq!(one_region.err(), Some(ParseError::InvalidRegions));
+    }
+
+    #[test]
+    fn constraints() {
+        let mut board = Board::new();
+        board.constraints = ["anti-knight", "non-consecutive", "odd:80"]
+            .iter()
+            .map(|word| constraint::parse(word, 81).unwrap())
+            .collect();
+        board.set((2, 0), Some(5));
+        // a knight's move away, in another row, column and segment
+        assert_eq!(board.place((3, 2), Some(5)), Err(PlaceError::Constraint));
+        assert_eq!(board.constraint_conflict(board.index((3, 2)), 5), Some(2));
+        assert_eq!(board.place((3, 0), Some(6)), Err(PlaceError::Constraint));
+        assert_eq!(board.constraint_conflict(3, 6), Some(3));
+        assert_eq!(board.place(80, Some(2)), Err(PlaceError::Constraint));
+        assert_eq!(board.place(80, Some(3)), Ok(()));
+
+        let windoku = constraint::parse("windoku", 81).unwrap();
+        assert_eq!(windoku.units(&board).len(), 4);
+        assert!(constraint::parse("odd:81", 81).is_none());
+
+        let empty = Board {
+            constraints: vec![windoku.clone(), constraint::parse("anti-king", 81).unwrap()],
+            ..Board::new()
+        };
+        let board = generate_from(&empty, Strategy::Unique(Difficulty::Easy), Seed(1)).unwrap();
+        assert_eq!(board.units().len(), 27 + 4);
+        let Solutions::Unique(solution) = solve::solutions(&board) else {
+            panic!("expected a unique solution");
+        };
+        for unit in windoku.units(&board) {
+            let mut nums: Vec<_> = unit.iter().map(|&i| solution.cells[i]).collect();
+            nums.sort();
+            assert_eq!(nums, (1..=9).map(Some).collect::<Vec<_>>());
+        }
+        for index in 0..81 {
+            for peer in board.constraint_peers(index) {
+                assert_ne!(solution.cells[index], solution.cells[peer]);
+            }
+        }
+
+        let puzzle = format::parse_line(&Format::Line.write(&board)).unwrap();
+        assert_eq!(puzzle.board, board);
+        assert!(!board.is_classic());
+    }
+
+    #[test]
+    fn windoku_turns() {
+        let shape = Shape::new(3, 2);
+        let empty = Board {
+            constraints: vec![constraint::parse("windoku", 36).unwrap()],
+            ..Board::with_shape(shape)
+        };
+        let board = generate_from(&empty, Strategy::Unique(Difficulty::Easy), Seed(1)).unwrap();
+
+        // the box is one cell from the left edge but two from the right one,
+        // so flipping would move it off the box the rule puts there
+        let flips = [board.flipped_horizontally(), board.flipped_vertically()];
+        for flipped in flips.into_iter().chain([board.rotated()]) {
+            match flipped {
+                Ok(flipped) => {
+                    assert!(matches!(solve::solutions(&flipped), Solutions::Unique(_)));
+                }
+                Err(e) => assert_eq!(e, TransformError::Move),
+            }
+        }
+        assert!(board.flipped_horizontally().is_err());
+        let transposed = board.transposed();
+        assert!(matches!(
+            solve::solutions(&transposed),
+            Solutions::Unique(_)
+        ));
+
+        // on square segments the boxes sit in the middle
+        let empty = Board {
+            constraints: vec![constraint::parse("windoku", 16).unwrap()],
+            ..Board::with_shape(Shape::new(2, 2))
+        };
+        let board = generate_from(&empty, Strategy::Unique(Difficulty::Easy), Seed(1)).unwrap();
+        let flipped = board.flipped_horizontally().unwrap();
+        assert!(matches!(solve::solutions(&flipped), Solutions::Unique(_)));
+        assert!(board.rotated().is_ok());
+    }
+
+    #[test]
+    fn line_constraints() {
+        let mut board = Board::new();
+        board.constraints = ["thermo:0,1,2", "arrow:27,28,29", "sandwich:r7:10"]
+            .iter()
+            .map(|word| constraint::parse(word, 81).unwrap())
+            .collect();
+        let words: Vec<String> = board.constraints.iter().map(|c| c.to_string()).collect();
+        assert_eq!(words, ["thermo:0,1,2", "arrow:27,28,29", "sandwich:r7:10"]);
+        assert!(constraint::parse("sandwich:r9:10", 81).is_none());
+        assert!(constraint::parse("thermo:3", 81).is_none());
+
+        // the bulb needs room for two larger numbers
+        assert_eq!(board.place(0, Some(8)), Err(PlaceError::Constraint));
+        board.set(1, Some(5));
+        assert_eq!(board.place(2, Some(4)), Err(PlaceError::Constraint));
+        assert_eq!(board.place(2, Some(6)), Ok(()));
+
+        board.set(27, Some(7));
+        board.set(28, Some(3));
+        assert_eq!(board.place(29, Some(5)), Err(PlaceError::Constraint));
+        assert_eq!(board.place(29, Some(4)), Ok(()));
+
+        // 1 and 9 around 7 and 3 in row 7
+        board.set((0, 7), Some(1));
+        board.set((3, 7), Some(9));
+        board.set((1, 7), Some(7));
+        assert_eq!(board.place((2, 7), Some(2)), Err(PlaceError::Constraint));
+        assert_eq!(board.place((2, 7), Some(3)), Ok(()));
+
+        board.lock_givens();
+        let puzzle = format::parse_line(&Format::Line.write(&board)).unwrap();
+        assert_eq!(puzzle.board, board);
+
+        let empty = Board {
+            constraints: vec![constraint::parse("thermo:0,1,2,3,4,5,6,7,8", 81).unwrap()],
+            ..Board::new()
+        };
+        let board = generate_from(&empty, Strategy::Unique(Difficulty::Easy), Seed(1)).unwrap();
+        let Solutions::Unique(solution) = solve::solutions(&board) else {
+            panic!("expected a unique solution");
+        };
+        let row: Vec<_> = solution.cells[..9].to_vec();
+        assert_eq!(row, (1..=9).map(Some).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn edge_constraints() {
+        let mut board = Board::new();
+        board.constraints = [
+            "white:0,1",
+            "black:30,31",
+            "x:60,61",
+            "v:70,79",
+            "xv-negative",
+        ]
+        .iter()
+        .map(|word| constraint::parse(word, 81).unwrap())
+        .collect();
+        assert!(constraint::parse("white:0,2", 81).is_none());
+        assert!(constraint::parse("white:8,9", 81).is_none());
+        assert!(constraint::parse("white:81,72", 81).is_none());
+        assert!(constraint::parse("x:72,81", 81).is_none());
+
+        board.set(0, Some(5));
+        assert_eq!(board.place(1, Some(7)), Err(PlaceError::Constraint));
+        assert_eq!(board.place(1, Some(6)), Ok(()));
+        board.set(30, Some(3));
+        assert_eq!(board.place(31, Some(4)), Err(PlaceError::Constraint));
+        assert_eq!(board.place(31, Some(6)), Ok(()));
+        assert_eq!(board.allowed(70), 0b1111);
+
+        // the X between them is given
+        board.set(60, Some(2));
+        assert_eq!(board.place(61, Some(8)), Ok(()));
+        // nothing is between these
+        board.set(50, Some(1));
+        assert_eq!(board.place(51, Some(9)), Err(PlaceError::Constraint));
+        assert_eq!(board.place(51, Some(4)), Err(PlaceError::Constraint));
+        assert_eq!(board.place(51, Some(6)), Ok(()));
+
+        board.lock_givens();
+        let puzzle = format::parse_line(&Format::Line.write(&board)).unwrap();
+        assert_eq!(puzzle.board, board);
+    }
+
+    #[test]
+    fn comparison_generated() {
+        let mut board = Board::new();
+        board.constraints = vec![constraint::parse("less:0,1", 81).unwrap()];
+        board.set(0, Some(9));
+        assert_eq!(board.allowed(1), 0);
+        assert_eq!(board.place(1, Some(3)), Err(PlaceError::Constraint));
+
+        let board = generate_from(
+            &Board::new(),
+            Strategy::Comparison(Difficulty::Easy),
+            Seed(1),
+        )
+        .unwrap();
+        assert!(board.clue_count() < 20);
+        let Solutions::Unique(solution) = solve::solutions(&board) else {
+            panic!("expected a unique solution");
+        };
+        for constraint in &board.constraints {
+            let Mark::Edge(EdgeKind::Less, a, b) = constraint.marks(&board)[0] else {
+                panic!("expected a comparison, got {constraint}");
+            };
+            assert!(solution.cells[a] < solution.cells[b]);
+        }
+        // 12 in each segment
+        assert_eq!(board.constraints.len(), 108);
+
+        let puzzle = format::parse_line(&Format::Line.write(&board)).unwrap();
+        assert_eq!(puzzle.board, board);
+    }
+
+    #[test]
+    fn gattai_grids() {
+        let mut samurai = Gattai::new(Layout::Samurai);
+        assert_eq!(samurai.side(), 21);
+        // the middle grid shares a segment with each of the others
+        assert_eq!(samurai.units().len(), 5 * 27 - 4);
+        assert_eq!(samurai.grids_at((7, 7)), vec![(0, 70), (2, 10)]);
+        assert_eq!(samurai.place((10, 0), Some(1)), Err(PlaceError::Given));
+
+        // in the top-left grid's last row, and the middle grid's second row
+        samurai.place((8, 7), Some(4)).unwrap();
+        assert_eq!(
+            samurai.place((14, 7), Some(4)),
+            Err(PlaceError::AlreadyInRow)
+        );
+        assert_eq!(
+            samurai.place((0, 7), Some(4)),
+            Err(PlaceError::AlreadyInRow)
+        );
+
+        let twin = Gattai::generate(Layout::Twin, Seed(1));
+        assert!(twin.is_unique());
+        let solution = twin.solve().unwrap();
+        assert!(solution.is_solved());
+        for g in 0..2 {
+            let grid: Board = solution.grid(g).line().parse().unwrap();
+            assert!(grid.is_solved());
+        }
+        assert_eq!(Gattai::from_line(Layout::Twin, &twin.line()), Ok(twin));
+    }
+
+    #[test]
+    fn cell_colors() {
+        let puzzle = Board::new();
+        let mut board = puzzle.clone();
+        board.toggle_color(12, 1);
+        board.toggle_color(12, 3);
+        board.toggle_color(40, 5);
+        board.toggle_color(40, 9);
+        board.toggle_color(40, 9);
+        assert_eq!(board.cell_colors(12), vec![1, 3]);
+        assert_eq!(board.colors_line().unwrap(), "12:13 40:5");
+        assert_ne!(board, puzzle);
+
+        // cells and colors that aren't on the board are skipped
+        let mut saved = Board::new();
+        saved.read_colors("12:13 40:5 81:1 7:x".split(' '));
+        assert_eq!(puzzle.restore(&saved), board);
+
+        // colors move along with their cells, (3, 1) to (1, 3)
+        let moved = board.transposed();
+        assert_eq!(moved.colors_line().unwrap(), "28:13 40:5");
+        assert!(board.empty().colors_line().is_none());
+    }
+
+    #[test]
+    fn jigsaw_progress() {
+        // every row is a region
+        let regions: String = (1..=9).map(|row| row.to_string().repeat(9)).collect();
+        let line = format!("5{} regions {regions}", ".".repeat(80));
+        let puzzle = format::parse_line(&line).unwrap().board;
+        let mut progress = puzzle.clone();
+        progress.place(10, Some(5)).unwrap();
+        progress.place(20, Some(3)).unwrap();
+        progress.toggle_color(3, 2);
+
+        // the numbers only follow the regions, not the standard segments
+        let line = progress.line();
+        assert!(line.parse::<Board>().is_err());
+        let mut restored = puzzle.restore_line(&line).unwrap();
+        restored.read_colors(progress.colors_line().unwrap().split(' '));
+        assert_eq!(restored, progress);
+
+        assert_eq!(puzzle.restore_line(&line.replace('.', "x")), None);
+    }
+
+    #[test]
+    fn pencil_marks() {
+        let mut board: Board = PUZZLE.parse().unwrap();
+        board.toggle_pencil(Pencil::Corner, 2, 1);
+        board.toggle_pencil(Pencil::Corner, 2, 4);
+        board.toggle_pencil(Pencil::Center, 2, 2);
+        board.toggle_pencil(Pencil::Center, 2, 2);
+        assert_eq!(board.pencil_marks(Pencil::Corner, 2), vec![1, 4]);
+        assert_eq!(board.pencil_line(Pencil::Corner).unwrap(), "2:14");
+        assert!(board.pencil_line(Pencil::Center).is_none());
+
+        let mut saved = Board::new();
+        saved.read_pencil(Pencil::Corner, ["2:14", "3:A"]);
+        assert_eq!(saved.corner_marks, board.corner_marks);
+
+        board.clear_pencil(2);
+        assert!(board.pencil_line(Pencil::Corner).is_none());
+    }
+
+    #[test]
+    fn hints_ignore_wrong_numbers() {
+        let mut board: Board = PUZZLE.parse().unwrap();
+        board.place((2, 0), Some(1)).unwrap();
+        let solution = board.solution().unwrap();
+        assert_eq!(solution.line(), SOLUTION);
+        assert_eq!(solution.cells[2], Some(4));
+    }
+
+    #[test]
+    fn fpuzzles_puzzles() {
+        // what lz-string's compressToBase64 gives
+        assert_eq!(fpuzzles::compress("hello"), "BYUwNmD2Q===");
+        assert_eq!(fpuzzles::decompress("BYUwNmD2Q===").unwrap(), "hello");
+
+        let mut rows = vec![vec!["{}"; 9]; 9];
+        rows[0][0] = r#"{"value":5,"given":true}"#;
+        rows[0][1] = r#"{"value":3}"#;
+        rows[8][8] = r##"{"c":"#FF0000"}"##;
+        let grid: Vec<String> = rows
+            .iter()
+            .map(|row| format!("[{}]", row.join(",")))
+            .collect();
+        let json = format!(
+            r#"{{"size":9,"title":"Test","grid":[{}],
+            "killercage":[{{"cells":["R1C2","R1C3"],"value":"10"}}],
+            "thermometer":[{{"lines":[["R2C1","R2C2","R2C3"]]}}],
+            "difference":[{{"cells":["R9C1","R9C2"]}}],
+            "negative":["ratio","difference"],
+            "odd":[{{"cell":"R5C5"}}],
+            "sandwichsum":[{{"cell":"R0C4","value":"15"}}],
+            "line":[{{"lines":[["R1C1","R9C9"]]}}]}}"#,
+            grid.join(",")
+        );
+
+        let puzzles = fpuzzles::read(&json).unwrap();
+        let board = &puzzles[0].board;
+        assert_eq!(puzzles[0].comment, "Test");
+        assert_eq!(board.cells[0], Some(5));
+        assert_eq!(board.clue_count(), 1);
+        assert_eq!(board.cages, vec![Cage::new(vec![1, 2], 10)]);
+        let words: Vec<String> = board.constraints.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            words,
+            [
+                "thermo:9,10,11",
+                "white:72,73",
+                "kropki-negative",
+                "odd:40",
+                "sandwich:c3:15",
+                "color:ff0000:80",
+            ]
+        );
+
+        // the same puzzle through a link, and back out again
+        let link = format!(
+            "https://www.f-puzzles.com/?load={}",
+            fpuzzles::compress(&json).replace('+', "%2B")
+        );
+        assert!(fpuzzles::is_fpuzzles(&link));
+        assert_eq!(Format::FPuzzles.read(&link).unwrap()[0], *board);
+        let written = Format::FPuzzles
+            .read(&Format::FPuzzles.write(board))
+            .unwrap();
+        let mut again: Vec<String> = written[0]
+            .constraints
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        again.sort();
+        let mut words = words;
+        words.sort();
+        assert_eq!(again, words);
+        assert_eq!(
+            (&written[0].cells, &written[0].cages),
+            (&board.cells, &board.cages)
+        );
+
+        let renban = json.replace("\"line\"", "\"renban\"");
+        assert_eq!(
+            fpuzzles::read(&renban).err(),
+            Some(ParseError::Unsupported("renban"))
+        );
+
+        // comparisons have nothing to go into
+        assert!(fpuzzles::left_out(board).is_empty());
+        let mut compared = board.clone();
+        compared
+            .constraints
+            .push(constraint::parse("less:0,1", 81).unwrap());
+        assert_eq!(fpuzzles::left_out(&compared), ["less:0,1"]);
+    }
+}